@@ -0,0 +1,911 @@
+//! Support for building ACPI tables, rather than parsing them. This is useful for hypervisors and firmware that
+//! need to provide tables to a guest or OS, and for creating synthetic tables to test code that consumes them.
+//!
+//! Each table has a builder that implements [`TableBuilder`], which produces the bytes of the table with a correct
+//! length and checksum. [`AcpiTablesBuilder`] lays a set of tables out in memory, behind an RSDP and XSDT, and
+//! links the FADT to the DSDT:
+//! ```
+//! # #[cfg(feature = "builder")] {
+//! use acpi::builder::{AcpiTablesBuilder, FadtBuilder, MadtBuilder, OemInfo};
+//!
+//! // An empty definition block. This would usually be the output of an AML compiler.
+//! let aml: &[u8] = &[];
+//!
+//! let mut madt = MadtBuilder::new(0xfee0_0000);
+//! madt.local_apic(0, 0, true).io_apic(0, 0xfec0_0000, 0);
+//!
+//! let mut builder = AcpiTablesBuilder::new(OemInfo::default());
+//! builder.fadt(FadtBuilder::default()).dsdt(aml).table(&madt);
+//! let image = builder.build(0xe0000);
+//! // Copy `image.bytes` to physical address `0xe0000`, and point the guest at `image.rsdp_address()`.
+//! # assert_eq!(image.rsdp_address(), 0xe0000);
+//! # }
+//! ```
+//!
+//! This is enabled by the `builder` feature.
+
+use crate::{
+    fadt::PowerProfile,
+    hpet::PageProtection,
+    platform::address::{AccessSize, AddressSpace, GenericAddress},
+    sdt::{SdtHeader, Signature},
+    srat::{AffinityProcessor, MemoryAffinity, ProcessorAffinity},
+};
+use alloc::{vec, vec::Vec};
+use core::mem;
+
+/// The revision of the FADT produced by [`FadtBuilder`], which is the revision used by ACPI 6.0 onwards.
+const FADT_REVISION: u8 = 6;
+const FADT_MINOR_VERSION: u8 = 5;
+const FADT_LENGTH: usize = 276;
+
+const RSDP_LENGTH: usize = 36;
+const RSDP_V1_LENGTH: usize = 20;
+const RSDP_REVISION: u8 = 2;
+
+/// The offset of the checksum within the standard SDT header.
+const CHECKSUM_OFFSET: usize = 9;
+
+/// The values that are used to fill in the OEM and creator fields of the header of each table.
+#[derive(Clone, Copy, Debug)]
+pub struct OemInfo {
+    pub oem_id: [u8; 6],
+    pub oem_table_id: [u8; 8],
+    pub oem_revision: u32,
+    pub creator_id: u32,
+    pub creator_revision: u32,
+}
+
+impl Default for OemInfo {
+    fn default() -> OemInfo {
+        OemInfo {
+            oem_id: *b"RUST  ",
+            oem_table_id: *b"RUSTACPI",
+            oem_revision: 1,
+            creator_id: u32::from_le_bytes(*b"RUST"),
+            creator_revision: 1,
+        }
+    }
+}
+
+/// Implemented by the builder for each table. `build` produces the complete table, including its header.
+pub trait TableBuilder {
+    fn signature(&self) -> Signature;
+    fn revision(&self) -> u8;
+
+    /// Append the contents of the table that follow the standard header to `bytes`.
+    fn write_contents(&self, bytes: &mut Vec<u8>);
+
+    fn build(&self, oem: &OemInfo) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, self.signature(), self.revision(), oem);
+        self.write_contents(&mut bytes);
+        finish_table(&mut bytes);
+        bytes
+    }
+}
+
+/// Builds the FADT. All of the fields default to zero, and register blocks that are `None` are left empty. The
+/// address of the DSDT is filled in by [`AcpiTablesBuilder`] if it's given a DSDT. The addresses of the FACS and
+/// DSDT are written to the 32-bit fields if they fit, and to the 64-bit fields otherwise.
+///
+/// Register blocks are written to both the extended (`X_`) fields and, if they are in I/O space, to the legacy
+/// fields, so the table can be used by OSs that only understand older revisions of the FADT.
+#[derive(Clone, Debug)]
+pub struct FadtBuilder {
+    pub firmware_ctrl: u64,
+    pub dsdt_address: u64,
+    pub preferred_pm_profile: PowerProfile,
+    pub sci_interrupt: u16,
+    pub smi_cmd_port: u32,
+    pub acpi_enable: u8,
+    pub acpi_disable: u8,
+    pub pm1a_event_block: Option<GenericAddress>,
+    pub pm1b_event_block: Option<GenericAddress>,
+    pub pm1a_control_block: Option<GenericAddress>,
+    pub pm1b_control_block: Option<GenericAddress>,
+    pub pm2_control_block: Option<GenericAddress>,
+    pub pm_timer_block: Option<GenericAddress>,
+    pub gpe0_block: Option<GenericAddress>,
+    /// The length of the GPE0 block in bytes. This is separate from `gpe0_block`, as GPE blocks can be too long
+    /// for their width to fit in the `bit_width` field.
+    pub gpe0_block_length: u8,
+    pub gpe1_block: Option<GenericAddress>,
+    pub gpe1_block_length: u8,
+    pub gpe1_base: u8,
+    pub century: u8,
+    pub iapc_boot_arch: u16,
+    /// The fixed feature flags, in the format returned by `FixedFeatureFlags::bits`.
+    pub flags: u32,
+    pub reset_register: Option<GenericAddress>,
+    pub reset_value: u8,
+    pub arm_boot_arch: u16,
+    pub sleep_control_register: Option<GenericAddress>,
+    pub sleep_status_register: Option<GenericAddress>,
+    pub hypervisor_vendor_id: u64,
+}
+
+impl Default for FadtBuilder {
+    fn default() -> FadtBuilder {
+        FadtBuilder {
+            firmware_ctrl: 0,
+            dsdt_address: 0,
+            preferred_pm_profile: PowerProfile::Unspecified,
+            sci_interrupt: 0,
+            smi_cmd_port: 0,
+            acpi_enable: 0,
+            acpi_disable: 0,
+            pm1a_event_block: None,
+            pm1b_event_block: None,
+            pm1a_control_block: None,
+            pm1b_control_block: None,
+            pm2_control_block: None,
+            pm_timer_block: None,
+            gpe0_block: None,
+            gpe0_block_length: 0,
+            gpe1_block: None,
+            gpe1_block_length: 0,
+            gpe1_base: 0,
+            century: 0,
+            iapc_boot_arch: 0,
+            flags: 0,
+            reset_register: None,
+            reset_value: 0,
+            arm_boot_arch: 0,
+            sleep_control_register: None,
+            sleep_status_register: None,
+            hypervisor_vendor_id: 0,
+        }
+    }
+}
+
+impl TableBuilder for FadtBuilder {
+    fn signature(&self) -> Signature {
+        Signature::FADT
+    }
+
+    fn revision(&self) -> u8 {
+        FADT_REVISION
+    }
+
+    fn write_contents(&self, bytes: &mut Vec<u8>) {
+        let start = bytes.len();
+        bytes.resize(start + FADT_LENGTH - mem::size_of::<SdtHeader>(), 0);
+        let fadt = &mut bytes[start..];
+
+        /*
+         * These offsets are relative to the end of the header.
+         */
+        write_pointer(fadt, 0, 96, self.firmware_ctrl);
+        write_pointer(fadt, 4, 104, self.dsdt_address);
+        put(fadt, 9, &[power_profile_to_raw(self.preferred_pm_profile)]);
+        put(fadt, 10, &self.sci_interrupt.to_le_bytes());
+        put(fadt, 12, &self.smi_cmd_port.to_le_bytes());
+        put(fadt, 16, &[self.acpi_enable, self.acpi_disable]);
+
+        let blocks = [
+            (20, 52, 112, &self.pm1a_event_block),
+            (24, 52, 124, &self.pm1b_event_block),
+            (28, 53, 136, &self.pm1a_control_block),
+            (32, 53, 148, &self.pm1b_control_block),
+            (36, 54, 160, &self.pm2_control_block),
+            (40, 55, 172, &self.pm_timer_block),
+        ];
+        for &(legacy_offset, length_offset, x_offset, block) in blocks.iter() {
+            write_block(fadt, legacy_offset, Some(length_offset), x_offset, block);
+        }
+        write_block(fadt, 44, None, 184, &self.gpe0_block);
+        write_block(fadt, 48, None, 196, &self.gpe1_block);
+        put(fadt, 56, &[self.gpe0_block_length, self.gpe1_block_length, self.gpe1_base]);
+
+        put(fadt, 72, &[self.century]);
+        put(fadt, 73, &self.iapc_boot_arch.to_le_bytes());
+        put(fadt, 76, &self.flags.to_le_bytes());
+        if let Some(ref reset_register) = self.reset_register {
+            put(fadt, 80, &raw_generic_address(reset_register));
+        }
+        put(fadt, 92, &[self.reset_value]);
+        put(fadt, 93, &self.arm_boot_arch.to_le_bytes());
+        put(fadt, 95, &[FADT_MINOR_VERSION]);
+
+        if let Some(ref sleep_control_register) = self.sleep_control_register {
+            put(fadt, 208, &raw_generic_address(sleep_control_register));
+        }
+        if let Some(ref sleep_status_register) = self.sleep_status_register {
+            put(fadt, 220, &raw_generic_address(sleep_status_register));
+        }
+        put(fadt, 232, &self.hypervisor_vendor_id.to_le_bytes());
+    }
+}
+
+/// Builds the MADT. Entries are written in the order they are added.
+#[derive(Clone, Debug)]
+pub struct MadtBuilder {
+    local_apic_address: u32,
+    flags: u32,
+    entries: Vec<u8>,
+}
+
+impl MadtBuilder {
+    pub fn new(local_apic_address: u32) -> MadtBuilder {
+        MadtBuilder { local_apic_address, flags: 0, entries: Vec::new() }
+    }
+
+    /// Set whether the system also has a pair of legacy 8259 PICs (the `PCAT_COMPAT` flag).
+    pub fn supports_8259(&mut self, supports_8259: bool) -> &mut MadtBuilder {
+        self.flags = supports_8259 as u32;
+        self
+    }
+
+    pub fn local_apic(&mut self, processor_id: u8, apic_id: u8, enabled: bool) -> &mut MadtBuilder {
+        let mut entry = [0; 6];
+        entry[0] = processor_id;
+        entry[1] = apic_id;
+        entry[2..6].copy_from_slice(&(enabled as u32).to_le_bytes());
+        self.raw_entry(0x0, &entry)
+    }
+
+    pub fn io_apic(
+        &mut self,
+        io_apic_id: u8,
+        address: u32,
+        global_system_interrupt_base: u32,
+    ) -> &mut MadtBuilder {
+        let mut entry = [0; 10];
+        entry[0] = io_apic_id;
+        entry[2..6].copy_from_slice(&address.to_le_bytes());
+        entry[6..10].copy_from_slice(&global_system_interrupt_base.to_le_bytes());
+        self.raw_entry(0x1, &entry)
+    }
+
+    /// Add an Interrupt Source Override. `flags` encodes the polarity and trigger mode of the interrupt, in the
+    /// same format as the MPS INTI flags.
+    pub fn interrupt_source_override(
+        &mut self,
+        bus: u8,
+        irq: u8,
+        global_system_interrupt: u32,
+        flags: u16,
+    ) -> &mut MadtBuilder {
+        let mut entry = [0; 8];
+        entry[0] = bus;
+        entry[1] = irq;
+        entry[2..6].copy_from_slice(&global_system_interrupt.to_le_bytes());
+        entry[6..8].copy_from_slice(&flags.to_le_bytes());
+        self.raw_entry(0x2, &entry)
+    }
+
+    pub fn nmi_source(&mut self, global_system_interrupt: u32, flags: u16) -> &mut MadtBuilder {
+        let mut entry = [0; 6];
+        entry[0..2].copy_from_slice(&flags.to_le_bytes());
+        entry[2..6].copy_from_slice(&global_system_interrupt.to_le_bytes());
+        self.raw_entry(0x3, &entry)
+    }
+
+    /// Describe which `LINTn` input of a local APIC the NMI is connected to. A `processor_id` of `0xff` applies to
+    /// all processors.
+    pub fn local_apic_nmi(&mut self, processor_id: u8, flags: u16, nmi_line: u8) -> &mut MadtBuilder {
+        let mut entry = [0; 4];
+        entry[0] = processor_id;
+        entry[1..3].copy_from_slice(&flags.to_le_bytes());
+        entry[3] = nmi_line;
+        self.raw_entry(0x4, &entry)
+    }
+
+    pub fn local_apic_address_override(&mut self, local_apic_address: u64) -> &mut MadtBuilder {
+        let mut entry = [0; 10];
+        entry[2..10].copy_from_slice(&local_apic_address.to_le_bytes());
+        self.raw_entry(0x5, &entry)
+    }
+
+    pub fn local_x2apic(&mut self, x2apic_id: u32, processor_uid: u32, enabled: bool) -> &mut MadtBuilder {
+        let mut entry = [0; 14];
+        entry[2..6].copy_from_slice(&x2apic_id.to_le_bytes());
+        entry[6..10].copy_from_slice(&(enabled as u32).to_le_bytes());
+        entry[10..14].copy_from_slice(&processor_uid.to_le_bytes());
+        self.raw_entry(0x9, &entry)
+    }
+
+    /// Describe which `LINTn` input of a local x2APIC the NMI is connected to. A `processor_uid` of `0xffffffff`
+    /// applies to all processors.
+    pub fn x2apic_nmi(&mut self, processor_uid: u32, flags: u16, nmi_line: u8) -> &mut MadtBuilder {
+        let mut entry = [0; 10];
+        entry[0..2].copy_from_slice(&flags.to_le_bytes());
+        entry[2..6].copy_from_slice(&processor_uid.to_le_bytes());
+        entry[6] = nmi_line;
+        self.raw_entry(0xa, &entry)
+    }
+
+    /// Add an entry of any type. `contents` is the entry without its two-byte header, which is added for you.
+    ///
+    /// ### Panics
+    /// Panics if the entry is too long for its length to fit in the header.
+    pub fn raw_entry(&mut self, entry_type: u8, contents: &[u8]) -> &mut MadtBuilder {
+        let length = contents.len() + 2;
+        assert!(length <= u8::MAX as usize, "MADT entry is too long");
+
+        self.entries.push(entry_type);
+        self.entries.push(length as u8);
+        self.entries.extend_from_slice(contents);
+        self
+    }
+}
+
+impl TableBuilder for MadtBuilder {
+    fn signature(&self) -> Signature {
+        Signature::MADT
+    }
+
+    fn revision(&self) -> u8 {
+        5
+    }
+
+    fn write_contents(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.local_apic_address.to_le_bytes());
+        bytes.extend_from_slice(&self.flags.to_le_bytes());
+        bytes.extend_from_slice(&self.entries);
+    }
+}
+
+/// Builds the MCFG, which describes where the configuration space of each range of PCIe buses is mapped.
+#[derive(Clone, Debug, Default)]
+pub struct McfgBuilder {
+    entries: Vec<u8>,
+}
+
+impl McfgBuilder {
+    pub fn new() -> McfgBuilder {
+        McfgBuilder { entries: Vec::new() }
+    }
+
+    pub fn region(
+        &mut self,
+        base_address: u64,
+        pci_segment_group: u16,
+        bus_number_start: u8,
+        bus_number_end: u8,
+    ) -> &mut McfgBuilder {
+        self.entries.extend_from_slice(&base_address.to_le_bytes());
+        self.entries.extend_from_slice(&pci_segment_group.to_le_bytes());
+        self.entries.extend_from_slice(&[bus_number_start, bus_number_end]);
+        self.entries.extend_from_slice(&[0; 4]);
+        self
+    }
+}
+
+impl TableBuilder for McfgBuilder {
+    fn signature(&self) -> Signature {
+        Signature::MCFG
+    }
+
+    fn revision(&self) -> u8 {
+        1
+    }
+
+    fn write_contents(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(&self.entries);
+    }
+}
+
+/// Builds the HPET table. The HPET is always described as being in system memory.
+#[derive(Clone, Debug)]
+pub struct HpetBuilder {
+    pub event_timer_block_id: u32,
+    pub base_address: u64,
+    pub hpet_number: u8,
+    pub clock_tick_unit: u16,
+    /// `PageProtection::Other` is written as the first reserved value.
+    pub page_protection: PageProtection,
+}
+
+impl Default for HpetBuilder {
+    fn default() -> HpetBuilder {
+        HpetBuilder {
+            event_timer_block_id: 0,
+            base_address: 0,
+            hpet_number: 0,
+            clock_tick_unit: 0,
+            page_protection: PageProtection::None,
+        }
+    }
+}
+
+impl TableBuilder for HpetBuilder {
+    fn signature(&self) -> Signature {
+        Signature::HPET
+    }
+
+    fn revision(&self) -> u8 {
+        1
+    }
+
+    fn write_contents(&self, bytes: &mut Vec<u8>) {
+        let base_address = GenericAddress {
+            address_space: AddressSpace::SystemMemory,
+            bit_width: 64,
+            bit_offset: 0,
+            access_size: AccessSize::Undefined,
+            address: self.base_address,
+        };
+
+        bytes.extend_from_slice(&self.event_timer_block_id.to_le_bytes());
+        bytes.extend_from_slice(&raw_generic_address(&base_address));
+        bytes.push(self.hpet_number);
+        bytes.extend_from_slice(&self.clock_tick_unit.to_le_bytes());
+        bytes.push(match self.page_protection {
+            PageProtection::None => 0,
+            PageProtection::Protected4K => 1,
+            PageProtection::Protected64K => 2,
+            PageProtection::Other => 3,
+        });
+    }
+}
+
+/// Builds the SRAT. Every processor and range of memory that is added is marked as enabled.
+#[derive(Clone, Debug, Default)]
+pub struct SratBuilder {
+    entries: Vec<u8>,
+}
+
+impl SratBuilder {
+    pub fn new() -> SratBuilder {
+        SratBuilder { entries: Vec::new() }
+    }
+
+    pub fn processor_affinity(&mut self, affinity: &ProcessorAffinity) -> &mut SratBuilder {
+        let proximity_domain = affinity.proximity_domain.to_le_bytes();
+        let clock_domain = affinity.clock_domain.to_le_bytes();
+
+        match affinity.processor {
+            AffinityProcessor::LocalApic { apic_id, local_sapic_eid } => {
+                self.entries.extend_from_slice(&[0x0, 16, proximity_domain[0], apic_id]);
+                self.entries.extend_from_slice(&1u32.to_le_bytes());
+                self.entries.extend_from_slice(&[local_sapic_eid]);
+                self.entries.extend_from_slice(&proximity_domain[1..4]);
+                self.entries.extend_from_slice(&clock_domain);
+            }
+            AffinityProcessor::X2Apic { x2apic_id } => {
+                self.entries.extend_from_slice(&[0x2, 24, 0, 0]);
+                self.entries.extend_from_slice(&proximity_domain);
+                self.entries.extend_from_slice(&x2apic_id.to_le_bytes());
+                self.entries.extend_from_slice(&1u32.to_le_bytes());
+                self.entries.extend_from_slice(&clock_domain);
+                self.entries.extend_from_slice(&[0; 4]);
+            }
+            AffinityProcessor::Gicc { processor_uid } => {
+                self.entries.extend_from_slice(&[0x3, 18]);
+                self.entries.extend_from_slice(&proximity_domain);
+                self.entries.extend_from_slice(&processor_uid.to_le_bytes());
+                self.entries.extend_from_slice(&1u32.to_le_bytes());
+                self.entries.extend_from_slice(&clock_domain);
+            }
+        }
+
+        self
+    }
+
+    pub fn memory_affinity(&mut self, affinity: &MemoryAffinity) -> &mut SratBuilder {
+        let mut flags = 1u32;
+        if affinity.hot_pluggable {
+            flags |= 1 << 1;
+        }
+        if affinity.non_volatile {
+            flags |= 1 << 2;
+        }
+
+        self.entries.extend_from_slice(&[0x1, 40]);
+        self.entries.extend_from_slice(&affinity.proximity_domain.to_le_bytes());
+        self.entries.extend_from_slice(&[0; 2]);
+        self.entries.extend_from_slice(&affinity.base_address.to_le_bytes());
+        self.entries.extend_from_slice(&affinity.length.to_le_bytes());
+        self.entries.extend_from_slice(&[0; 4]);
+        self.entries.extend_from_slice(&flags.to_le_bytes());
+        self.entries.extend_from_slice(&[0; 8]);
+        self
+    }
+}
+
+impl TableBuilder for SratBuilder {
+    fn signature(&self) -> Signature {
+        Signature::SRAT
+    }
+
+    fn revision(&self) -> u8 {
+        3
+    }
+
+    fn write_contents(&self, bytes: &mut Vec<u8>) {
+        /*
+         * The first reserved field must be `1`, for compatibility with older OSs.
+         */
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(&self.entries);
+    }
+}
+
+/// The result of [`AcpiTablesBuilder::build`]. `bytes` should be placed at `base_address` in physical memory, and
+/// starts with the RSDP.
+#[derive(Clone, Debug)]
+pub struct TableImage {
+    pub base_address: u64,
+    pub bytes: Vec<u8>,
+}
+
+impl TableImage {
+    pub fn rsdp_address(&self) -> u64 {
+        self.base_address
+    }
+}
+
+/// Lays out a complete set of tables, with an RSDP and an XSDT that points to each of the tables. The RSDP is
+/// always of revision 2, and the tables are only listed by the XSDT, so the RSDT address of the RSDP is zero.
+#[derive(Clone, Debug)]
+pub struct AcpiTablesBuilder {
+    oem: OemInfo,
+    fadt: Option<FadtBuilder>,
+    dsdt: Option<Vec<u8>>,
+    tables: Vec<Vec<u8>>,
+}
+
+impl AcpiTablesBuilder {
+    pub fn new(oem: OemInfo) -> AcpiTablesBuilder {
+        AcpiTablesBuilder { oem, fadt: None, dsdt: None, tables: Vec::new() }
+    }
+
+    /// Add the FADT. If a DSDT is also added, the FADT is pointed at it when the tables are built.
+    pub fn fadt(&mut self, fadt: FadtBuilder) -> &mut AcpiTablesBuilder {
+        self.fadt = Some(fadt);
+        self
+    }
+
+    /// Add a DSDT that contains the given AML.
+    pub fn dsdt(&mut self, aml: &[u8]) -> &mut AcpiTablesBuilder {
+        let mut dsdt = Vec::new();
+        write_header(&mut dsdt, Signature::DSDT, 2, &self.oem);
+        dsdt.extend_from_slice(aml);
+        finish_table(&mut dsdt);
+
+        self.dsdt = Some(dsdt);
+        self
+    }
+
+    /// Build a table, and add it to the XSDT.
+    pub fn table<T>(&mut self, table: &T) -> &mut AcpiTablesBuilder
+    where
+        T: TableBuilder,
+    {
+        self.tables.push(table.build(&self.oem));
+        self
+    }
+
+    /// Add a table that has already been built, including its header and checksum, to the XSDT.
+    pub fn raw_table(&mut self, table: Vec<u8>) -> &mut AcpiTablesBuilder {
+        self.tables.push(table);
+        self
+    }
+
+    /// Lay out the tables, to be placed at `base_address` in physical memory. The RSDP is placed first, followed
+    /// by the XSDT and each table, at 8-byte aligned offsets. `base_address` should be 16-byte aligned, as the
+    /// RSDP must be when it is found by searching memory.
+    pub fn build(&self, base_address: u64) -> TableImage {
+        let xsdt_offset = align_up(RSDP_LENGTH, 8);
+        let num_tables = self.tables.len() + self.fadt.is_some() as usize;
+        let xsdt_length = mem::size_of::<SdtHeader>() + num_tables * mem::size_of::<u64>();
+
+        let mut offset = align_up(xsdt_offset + xsdt_length, 8);
+        let mut place = |length: usize| {
+            let table_offset = offset;
+            offset = align_up(offset + length, 8);
+            table_offset
+        };
+
+        /*
+         * The DSDT has to be placed before the FADT is built, so the FADT can point at it.
+         */
+        let dsdt = self.dsdt.as_ref().map(|dsdt| (place(dsdt.len()), dsdt.clone()));
+        let fadt = self.fadt.as_ref().map(|fadt| {
+            let mut fadt = fadt.clone();
+            if let Some((dsdt_offset, _)) = &dsdt {
+                fadt.dsdt_address = base_address + *dsdt_offset as u64;
+            }
+            let fadt = fadt.build(&self.oem);
+            (place(fadt.len()), fadt)
+        });
+        let tables: Vec<(usize, Vec<u8>)> =
+            self.tables.iter().map(|table| (place(table.len()), table.clone())).collect();
+
+        let mut xsdt = Vec::with_capacity(xsdt_length);
+        write_header(&mut xsdt, Signature::XSDT, 1, &self.oem);
+        for &(table_offset, _) in fadt.iter().chain(tables.iter()) {
+            xsdt.extend_from_slice(&(base_address + table_offset as u64).to_le_bytes());
+        }
+        finish_table(&mut xsdt);
+
+        let mut bytes = vec![0; offset];
+        write_rsdp(&mut bytes[0..RSDP_LENGTH], base_address + xsdt_offset as u64, &self.oem);
+        put(&mut bytes, xsdt_offset, &xsdt);
+        for (table_offset, table) in dsdt.iter().chain(fadt.iter()).chain(tables.iter()) {
+            put(&mut bytes, *table_offset, table);
+        }
+
+        TableImage { base_address, bytes }
+    }
+}
+
+fn write_rsdp(rsdp: &mut [u8], xsdt_address: u64, oem: &OemInfo) {
+    put(rsdp, 0, b"RSD PTR ");
+    put(rsdp, 9, &oem.oem_id);
+    put(rsdp, 15, &[RSDP_REVISION]);
+    put(rsdp, 20, &(RSDP_LENGTH as u32).to_le_bytes());
+    put(rsdp, 24, &xsdt_address.to_le_bytes());
+
+    /*
+     * The first checksum only covers the ACPI 1.0 part of the RSDP, while the extended checksum covers all of it.
+     */
+    rsdp[8] = checksum(&rsdp[0..RSDP_V1_LENGTH]);
+    rsdp[32] = checksum(rsdp);
+}
+
+fn write_header(bytes: &mut Vec<u8>, signature: Signature, revision: u8, oem: &OemInfo) {
+    bytes.extend_from_slice(signature.as_str().as_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.push(revision);
+    bytes.push(0);
+    bytes.extend_from_slice(&oem.oem_id);
+    bytes.extend_from_slice(&oem.oem_table_id);
+    bytes.extend_from_slice(&oem.oem_revision.to_le_bytes());
+    bytes.extend_from_slice(&oem.creator_id.to_le_bytes());
+    bytes.extend_from_slice(&oem.creator_revision.to_le_bytes());
+}
+
+/// Fill in the length and checksum of a table, once all of its contents have been written.
+fn finish_table(bytes: &mut [u8]) {
+    let length = bytes.len() as u32;
+    put(bytes, 4, &length.to_le_bytes());
+    bytes[CHECKSUM_OFFSET] = 0;
+    bytes[CHECKSUM_OFFSET] = checksum(bytes);
+}
+
+/// Calculate the value that makes the bytes sum to zero.
+fn checksum(bytes: &[u8]) -> u8 {
+    0u8.wrapping_sub(bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)))
+}
+
+/// Write one of the FADT's register blocks to its extended field, and also to its legacy field if it's in I/O
+/// space and can be described by one.
+fn write_block(
+    fadt: &mut [u8],
+    legacy_offset: usize,
+    length_offset: Option<usize>,
+    x_offset: usize,
+    block: &Option<GenericAddress>,
+) {
+    let block = match block {
+        Some(block) => block,
+        None => return,
+    };
+
+    put(fadt, x_offset, &raw_generic_address(block));
+    if block.address_space == AddressSpace::SystemIo && block.address <= u32::MAX as u64 {
+        put(fadt, legacy_offset, &(block.address as u32).to_le_bytes());
+        if let Some(length_offset) = length_offset {
+            put(fadt, length_offset, &[block.bit_width / 8]);
+        }
+    }
+}
+
+fn raw_generic_address(address: &GenericAddress) -> [u8; 12] {
+    let address_space = match address.address_space {
+        AddressSpace::SystemMemory => 0x00,
+        AddressSpace::SystemIo => 0x01,
+        AddressSpace::PciConfigSpace => 0x02,
+        AddressSpace::EmbeddedController => 0x03,
+        AddressSpace::SMBus => 0x04,
+        AddressSpace::SystemCmos => 0x05,
+        AddressSpace::PciBarTarget => 0x06,
+        AddressSpace::Ipmi => 0x07,
+        AddressSpace::GeneralIo => 0x08,
+        AddressSpace::GenericSerialBus => 0x09,
+        AddressSpace::PlatformCommunicationsChannel => 0x0a,
+        AddressSpace::FunctionalFixedHardware => 0x7f,
+        AddressSpace::OemDefined(space) => space,
+    };
+    let access_size = match address.access_size {
+        AccessSize::Undefined => 0,
+        AccessSize::ByteAccess => 1,
+        AccessSize::WordAccess => 2,
+        AccessSize::DWordAccess => 3,
+        AccessSize::QWordAccess => 4,
+    };
+
+    let mut raw = [0; 12];
+    raw[0..4].copy_from_slice(&[address_space, address.bit_width, address.bit_offset, access_size]);
+    raw[4..12].copy_from_slice(&address.address.to_le_bytes());
+    raw
+}
+
+fn power_profile_to_raw(profile: PowerProfile) -> u8 {
+    match profile {
+        PowerProfile::Unspecified => 0,
+        PowerProfile::Desktop => 1,
+        PowerProfile::Mobile => 2,
+        PowerProfile::Workstation => 3,
+        PowerProfile::EnterpriseServer => 4,
+        PowerProfile::SohoServer => 5,
+        PowerProfile::AppliancePc => 6,
+        PowerProfile::PerformanceServer => 7,
+        PowerProfile::Tablet => 8,
+        PowerProfile::Reserved(other) => other,
+    }
+}
+
+/// Write one of the FADT's pointers to the FACS or DSDT. The 32-bit field is used if the address fits in it, and
+/// the 64-bit field otherwise, as only one of them should be non-zero.
+fn write_pointer(fadt: &mut [u8], legacy_offset: usize, x_offset: usize, address: u64) {
+    if address <= u32::MAX as u64 {
+        put(fadt, legacy_offset, &(address as u32).to_le_bytes());
+    } else {
+        put(fadt, x_offset, &address.to_le_bytes());
+    }
+}
+
+fn put(bytes: &mut [u8], offset: usize, value: &[u8]) {
+    bytes[offset..(offset + value.len())].copy_from_slice(value);
+}
+
+fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{
+        fadt::Fadt,
+        madt::Madt,
+        platform::{
+            interrupt::{LocalInterruptLine, NmiProcessor},
+            Processor,
+            ProcessorState,
+        },
+        AcpiHandler,
+        AcpiTables,
+        HpetInfo,
+        InterruptModel,
+        PciConfigRegions,
+        PhysicalMapping,
+    };
+    use core::{convert::TryInto, ptr::NonNull};
+
+    /// Maps physical addresses to the same virtual addresses, so tables built into a buffer can be read in place.
+    #[derive(Clone)]
+    struct IdentityHandler;
+
+    impl AcpiHandler for IdentityHandler {
+        unsafe fn map_physical_region<T>(&self, physical_address: usize, size: usize) -> PhysicalMapping<Self, T> {
+            PhysicalMapping {
+                physical_start: physical_address,
+                virtual_start: NonNull::new(physical_address as *mut T).unwrap(),
+                region_length: size,
+                mapped_length: size,
+                handler: self.clone(),
+            }
+        }
+
+        fn unmap_physical_region<T>(&self, _region: &PhysicalMapping<Self, T>) {}
+    }
+
+    /// Build the tables into a buffer, at the buffer's own address. The buffer must be kept alive for as long as
+    /// the tables are used.
+    fn build_in_place(builder: &AcpiTablesBuilder) -> (TableImage, Vec<u64>) {
+        let length = builder.build(0).bytes.len();
+        let mut buffer = vec![0u64; length.div_ceil(8)];
+        let image = builder.build(buffer.as_ptr() as u64);
+        unsafe { core::ptr::copy_nonoverlapping(image.bytes.as_ptr(), buffer.as_mut_ptr() as *mut u8, length) };
+        (image, buffer)
+    }
+
+    fn sum(bytes: &[u8]) -> u8 {
+        bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut madt = MadtBuilder::new(0xfee0_0000);
+        madt.supports_8259(true)
+            .local_apic(0, 0, true)
+            .local_apic(1, 2, true)
+            .io_apic(3, 0xfec0_0000, 0)
+            .interrupt_source_override(0, 0, 2, 0)
+            .local_apic_nmi(0xff, 0, 1);
+
+        let mut mcfg = McfgBuilder::new();
+        mcfg.region(0xb000_0000, 0, 0, 63).region(0xc000_0000, 1, 16, 31);
+
+        let hpet = HpetBuilder {
+            event_timer_block_id: 0x8086_a201,
+            base_address: 0xfed0_0000,
+            hpet_number: 1,
+            clock_tick_unit: 128,
+            page_protection: PageProtection::Protected4K,
+        };
+
+        let aml = [0x10, 0x05, 0x5c, 0x5f, 0x53, 0x42];
+        let mut builder = AcpiTablesBuilder::new(OemInfo::default());
+        builder.fadt(FadtBuilder::default()).dsdt(&aml).table(&madt).table(&mcfg).table(&hpet);
+        let (image, _buffer) = build_in_place(&builder);
+
+        /*
+         * Check the checksums of the RSDP and XSDT directly, as well as by loading the tables.
+         */
+        let bytes = &image.bytes;
+        assert_eq!(sum(&bytes[0..RSDP_V1_LENGTH]), 0);
+        assert_eq!(sum(&bytes[0..RSDP_LENGTH]), 0);
+        let xsdt_address = u64::from_le_bytes(bytes[24..32].try_into().unwrap());
+        let xsdt_offset = (xsdt_address - image.base_address) as usize;
+        let xsdt_length = u32::from_le_bytes(bytes[(xsdt_offset + 4)..(xsdt_offset + 8)].try_into().unwrap());
+        assert_eq!(&bytes[xsdt_offset..(xsdt_offset + 4)], b"XSDT");
+        assert_eq!(sum(&bytes[xsdt_offset..(xsdt_offset + xsdt_length as usize)]), 0);
+
+        let tables = unsafe { AcpiTables::from_rsdp(IdentityHandler, image.rsdp_address() as usize) }.unwrap();
+        assert!(tables.validation_report().is_clean());
+
+        let dsdt = tables.dsdt.as_ref().unwrap();
+        assert_eq!(dsdt.length as usize, aml.len());
+        let fadt = tables.get_table::<Fadt>().unwrap().unwrap();
+        let dsdt_address = fadt.dsdt_address().unwrap();
+        assert_eq!(dsdt_address, dsdt.address - mem::size_of::<SdtHeader>());
+        let dsdt_offset = dsdt_address - image.base_address as usize;
+        assert_eq!(&bytes[dsdt_offset..(dsdt_offset + 4)], b"DSDT");
+
+        let madt_table = tables.get_table::<Madt>().unwrap().unwrap();
+        assert!(madt_table.supports_8259());
+        assert_eq!(madt_table.entries().count(), 5);
+        let platform_info = tables.platform_info().unwrap();
+        let processor_info = platform_info.processor_info.unwrap();
+        assert_eq!(
+            processor_info.boot_processor,
+            Processor { processor_uid: 0, local_apic_id: 0, state: ProcessorState::Running, is_ap: false }
+        );
+        assert_eq!(
+            processor_info.application_processors,
+            [Processor { processor_uid: 1, local_apic_id: 2, state: ProcessorState::WaitingForSipi, is_ap: true }]
+        );
+        let apic = match platform_info.interrupt_model {
+            InterruptModel::Apic(apic) => apic,
+            other => panic!("unexpected interrupt model: {:?}", other),
+        };
+        assert_eq!(apic.local_apic_address, 0xfee0_0000);
+        assert!(apic.also_has_legacy_pics);
+        assert_eq!(apic.io_apics.len(), 1);
+        assert_eq!(apic.io_apics[0].id, 3);
+        assert_eq!(apic.io_apics[0].address, 0xfec0_0000);
+        assert_eq!(apic.io_apics[0].global_system_interrupt_base, 0);
+        assert_eq!(apic.interrupt_source_overrides.len(), 1);
+        assert_eq!(apic.interrupt_source_overrides[0].isa_source, 0);
+        assert_eq!(apic.interrupt_source_overrides[0].global_system_interrupt, 2);
+        assert_eq!(apic.local_apic_nmi_lines.len(), 1);
+        assert!(matches!(apic.local_apic_nmi_lines[0].processor, NmiProcessor::All));
+        assert!(matches!(apic.local_apic_nmi_lines[0].line, LocalInterruptLine::Lint1));
+
+        let regions = PciConfigRegions::new(&tables).unwrap();
+        assert_eq!(regions.physical_address(0, 0, 0, 0), Some(0xb000_0000));
+        assert_eq!(
+            regions.physical_address(0, 63, 31, 7),
+            Some(0xb000_0000 + (63 << 20) + (31 << 15) + (7 << 12))
+        );
+        assert_eq!(regions.physical_address(0, 64, 0, 0), None);
+        assert_eq!(regions.physical_address(1, 16, 0, 0), Some(0xc000_0000));
+        assert_eq!(regions.physical_address(1, 31, 0, 0), Some(0xc000_0000 + (15 << 20)));
+        assert_eq!(regions.physical_address(1, 15, 0, 0), None);
+
+        let hpet_info = HpetInfo::new(&tables).unwrap();
+        assert_eq!(hpet_info.event_timer_block_id, hpet.event_timer_block_id);
+        assert_eq!(hpet_info.base_address as u64, hpet.base_address);
+        assert_eq!(hpet_info.hpet_number, hpet.hpet_number);
+        assert_eq!(hpet_info.clock_tick_unit, hpet.clock_tick_unit);
+        assert_eq!(hpet_info.page_protection, hpet.page_protection);
+    }
+}
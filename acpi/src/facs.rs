@@ -0,0 +1,153 @@
+use crate::{sdt::Signature, AcpiError};
+use bit_field::BitField;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Set by the OS if it wants to own the global lock, but it is already owned by the firmware.
+const GLOBAL_LOCK_PENDING: u32 = 1 << 0;
+const GLOBAL_LOCK_OWNED: u32 = 1 << 1;
+
+/// The flags of the FACS, which are set by the firmware.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FacsFlags(u32);
+
+impl FacsFlags {
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// The platform supports entering the S4 state by writing `S4BIOS_REQ` to the `SMI_CMD` port.
+    pub fn s4bios(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// The platform firmware supports waking into a 64-bit environment through the 64-bit firmware waking vector.
+    pub fn wake_64bit_supported(&self) -> bool {
+        self.0.get_bit(1)
+    }
+}
+
+/// The flags of the FACS that are set by the OS, to tell the firmware how the system should be woken up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OspmFlags(u32);
+
+impl OspmFlags {
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// The OS has asked to be woken up in a 64-bit environment.
+    pub fn wake_64bit(&self) -> bool {
+        self.0.get_bit(0)
+    }
+}
+
+/// Represents the Firmware ACPI Control Structure (FACS). Unlike the other tables, this does not have a SDT header,
+/// and is instead found through the `firmware_ctrl` fields of the FADT - use
+/// [`AcpiTables::facs`](crate::AcpiTables::facs) to get hold of it. The FACS lives in memory that is shared with
+/// the firmware, and contains the waking vectors used to resume from sleep states, as well as the global lock.
+///
+/// Every field of the FACS is naturally aligned, and the FACS itself must be 64-byte aligned, so this is not
+/// `packed`.
+#[repr(C)]
+pub struct Facs {
+    signature: Signature,
+    length: u32,
+    hardware_signature: u32,
+    firmware_waking_vector: u32,
+    global_lock: AtomicU32,
+    flags: u32,
+    x_firmware_waking_vector: u64,
+    version: u8,
+    _reserved0: [u8; 3],
+    ospm_flags: u32,
+    _reserved1: [u8; 24],
+}
+
+impl Facs {
+    /// The smallest valid length of the FACS.
+    pub(crate) const MIN_LENGTH: u32 = 64;
+
+    pub fn validate(&self) -> Result<(), AcpiError> {
+        if self.signature != Signature::FACS {
+            return Err(AcpiError::SdtInvalidSignature(Signature::FACS));
+        }
+
+        if self.length < Self::MIN_LENGTH {
+            return Err(AcpiError::InvalidFacsLength);
+        }
+
+        Ok(())
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The hardware configuration of the platform, as calculated by the firmware at boot. If this has changed
+    /// after waking from S4, the OS should perform a cold boot instead of restoring the saved image.
+    pub fn hardware_signature(&self) -> u32 {
+        self.hardware_signature
+    }
+
+    /// The 32-bit physical address of the OS's waking vector, which the firmware jumps to in real mode when
+    /// waking from a sleep state. This is only used if the 64-bit waking vector is zero.
+    pub fn firmware_waking_vector(&self) -> u32 {
+        self.firmware_waking_vector
+    }
+
+    /// The 64-bit physical address of the OS's waking vector. If this is non-zero, it is used in preference to
+    /// the 32-bit waking vector. This was added in version 1 of the FACS, and so is `None` in older versions.
+    pub fn x_firmware_waking_vector(&self) -> Option<u64> {
+        if self.version >= 1 {
+            Some(self.x_firmware_waking_vector)
+        } else {
+            None
+        }
+    }
+
+    pub fn flags(&self) -> FacsFlags {
+        FacsFlags(self.flags)
+    }
+
+    /// The flags set by the OS. These were added in version 2 of the FACS, and so are empty in older versions.
+    pub fn ospm_flags(&self) -> OspmFlags {
+        OspmFlags(if self.version >= 2 { self.ospm_flags } else { 0 })
+    }
+
+    /// Try to acquire the global lock, which synchronises access to hardware shared between the OS and the
+    /// firmware. Returns `true` if the lock was acquired. If it returns `false`, the firmware currently owns the
+    /// lock, and has been told that the OS is waiting for it - the OS should wait for the firmware to raise a
+    /// `GBL_RLS` SCI before trying again.
+    #[must_use]
+    pub fn acquire_global_lock(&self) -> bool {
+        let mut old = self.global_lock.load(Ordering::Relaxed);
+        loop {
+            /*
+             * Mark the lock as owned. If it was already owned, mark it as pending instead, so the firmware knows
+             * to signal us when it releases it.
+             */
+            let mut new = (old & !GLOBAL_LOCK_PENDING) | GLOBAL_LOCK_OWNED;
+            if old & GLOBAL_LOCK_OWNED != 0 {
+                new |= GLOBAL_LOCK_PENDING;
+            }
+
+            match self.global_lock.compare_exchange_weak(old, new, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return new & GLOBAL_LOCK_PENDING == 0,
+                Err(current) => old = current,
+            }
+        }
+    }
+
+    /// Release the global lock, which must be owned by the OS. Returns `true` if the firmware was waiting for the
+    /// lock, in which case the OS must set the `GBL_RLS` bit in the PM1 control register to signal that it has
+    /// been released.
+    #[must_use]
+    pub fn release_global_lock(&self) -> bool {
+        let old = self.global_lock.fetch_and(!(GLOBAL_LOCK_OWNED | GLOBAL_LOCK_PENDING), Ordering::Release);
+        old & GLOBAL_LOCK_PENDING != 0
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{
     platform::address::{GenericAddress, RawGenericAddress},
-    sdt::{ExtendedField, SdtHeader},
+    sdt::{ExtendedField, SdtHeader, Signature},
     AcpiError,
     AcpiTable,
+    CheckedTable,
 };
+use bit_field::BitField;
+use core::mem;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum PowerProfile {
@@ -19,6 +22,217 @@ pub enum PowerProfile {
     Reserved(u8),
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HardwareModel {
+    /// The platform implements the full set of ACPI fixed hardware, such as the PM1 and GPE register blocks.
+    Full,
+    /// The platform uses the hardware-reduced ACPI model. The fixed hardware register blocks and the PM timer are
+    /// not implemented, and their FADT fields should be ignored. Sleep states are instead entered through the
+    /// sleep control and status registers.
+    Reduced,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PersistentCpuCaches {
+    NotReported,
+    NotPersistent,
+    Persistent,
+    Reserved,
+}
+
+/// The fixed feature flags of the FADT, which describe the capabilities of the fixed hardware and the platform.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FixedFeatureFlags(u32);
+
+impl FixedFeatureFlags {
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// The `WBINVD` instruction correctly flushes and invalidates all of the processor caches.
+    pub fn wbinvd(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// The `WBINVD` instruction flushes all of the processor caches, but may not invalidate them.
+    pub fn wbinvd_flush(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// The C1 power state is supported on all processors.
+    pub fn c1_supported(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    /// The C2 power state works on multiprocessor systems. If this is not set, C2 only works on uniprocessor
+    /// systems.
+    pub fn c2_multiprocessor(&self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// The power button is handled as a control method device, rather than as a fixed feature (or there is no
+    /// power button).
+    pub fn power_button_is_control_method(&self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// The sleep button is handled as a control method device, rather than as a fixed feature (or there is no
+    /// sleep button).
+    pub fn sleep_button_is_control_method(&self) -> bool {
+        self.0.get_bit(5)
+    }
+
+    /// The RTC wake status is not supported in fixed register space.
+    pub fn rtc_wake_not_fixed(&self) -> bool {
+        self.0.get_bit(6)
+    }
+
+    /// The RTC alarm can wake the system from the S4 state.
+    pub fn rtc_s4(&self) -> bool {
+        self.0.get_bit(7)
+    }
+
+    /// The PM timer is 32 bits wide. If this is not set, it is 24 bits wide.
+    pub fn pm_timer_is_32bit(&self) -> bool {
+        self.0.get_bit(8)
+    }
+
+    /// The system can support docking.
+    pub fn docking_supported(&self) -> bool {
+        self.0.get_bit(9)
+    }
+
+    /// The reset register described by the FADT can be used to reset the system.
+    pub fn reset_register_supported(&self) -> bool {
+        self.0.get_bit(10)
+    }
+
+    /// The system has no internal expansion capabilities, and the case is sealed.
+    pub fn sealed_case(&self) -> bool {
+        self.0.get_bit(11)
+    }
+
+    /// The system cannot detect the monitor or keyboard/mouse devices.
+    pub fn headless(&self) -> bool {
+        self.0.get_bit(12)
+    }
+
+    /// A processor native instruction must be executed after writing the `SLP_TYPx` register.
+    pub fn cpu_software_sleep(&self) -> bool {
+        self.0.get_bit(13)
+    }
+
+    /// The platform supports the `PCIEXP_WAKE_STS` and `PCIEXP_WAKE_EN` bits.
+    pub fn pci_express_wake(&self) -> bool {
+        self.0.get_bit(14)
+    }
+
+    /// OSPM should use a platform-provided timer to drive any monotonically non-decreasing counters.
+    pub fn use_platform_clock(&self) -> bool {
+        self.0.get_bit(15)
+    }
+
+    /// The contents of the `RTC_STS` flag are valid when waking the system from S4.
+    pub fn s4_rtc_status_valid(&self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    /// The platform is compatible with remote power-on.
+    pub fn remote_power_on_capable(&self) -> bool {
+        self.0.get_bit(17)
+    }
+
+    /// All local APICs must be configured for the cluster destination model.
+    pub fn force_apic_cluster_model(&self) -> bool {
+        self.0.get_bit(18)
+    }
+
+    /// All local x2APICs must be configured for physical destination mode.
+    pub fn force_apic_physical_destination_mode(&self) -> bool {
+        self.0.get_bit(19)
+    }
+
+    /// The ACPI hardware interface is not implemented, and the software-only alternatives must be used instead.
+    pub fn hardware_reduced_acpi(&self) -> bool {
+        self.0.get_bit(20)
+    }
+
+    /// The platform is able to achieve power savings in S0 similar to, or better than, those of S3.
+    pub fn low_power_s0_idle_capable(&self) -> bool {
+        self.0.get_bit(21)
+    }
+
+    pub fn persistent_cpu_caches(&self) -> PersistentCpuCaches {
+        match self.0.get_bits(22..24) {
+            0b00 => PersistentCpuCaches::NotReported,
+            0b01 => PersistentCpuCaches::NotPersistent,
+            0b10 => PersistentCpuCaches::Persistent,
+            _ => PersistentCpuCaches::Reserved,
+        }
+    }
+}
+
+/// Flags describing the legacy hardware of IA-PC systems.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IaPcBootArchFlags(u16);
+
+impl IaPcBootArchFlags {
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// The motherboard supports user-visible devices on the LPC or ISA bus, such as serial ports.
+    pub fn legacy_devices(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// The motherboard contains an 8042 (or equivalent) PS/2 keyboard controller.
+    pub fn has_8042(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// VGA hardware must not be probed for, as doing so may cause machine checks.
+    pub fn vga_not_present(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    /// Message Signaled Interrupts must not be enabled.
+    pub fn msi_not_supported(&self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// OSPM must not enable PCIe ASPM (Active State Power Management) controls.
+    pub fn pcie_aspm_controls(&self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// The CMOS RTC is not present, or is not implemented at its legacy address. The RTC should instead be
+    /// accessed through the `_GRT`/`_SRT` control methods.
+    pub fn cmos_rtc_not_present(&self) -> bool {
+        self.0.get_bit(5)
+    }
+}
+
+/// Flags describing the boot architecture of ARM systems.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ArmBootArchFlags(u16);
+
+impl ArmBootArchFlags {
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// The Power State Coordination Interface (PSCI) is implemented.
+    pub fn psci_compliant(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// PSCI calls must be made with the `HVC` instruction, rather than `SMC`.
+    pub fn psci_use_hvc(&self) -> bool {
+        self.0.get_bit(1)
+    }
+}
+
 /// Represents the Fixed ACPI Description Table (FADT). This table contains various fixed hardware
 /// details, such as the addresses of the hardware register blocks. It also contains a pointer to
 /// the Differentiated Definition Block (DSDT).
@@ -85,9 +299,9 @@ pub struct Fadt {
     x_pm_timer_block: ExtendedField<RawGenericAddress, 2>,
     x_gpe0_block: ExtendedField<RawGenericAddress, 2>,
     x_gpe1_block: ExtendedField<RawGenericAddress, 2>,
-    sleep_control_reg: ExtendedField<RawGenericAddress, 2>,
-    sleep_status_reg: ExtendedField<RawGenericAddress, 2>,
-    hypervisor_vendor_id: ExtendedField<u64, 2>,
+    sleep_control_reg: ExtendedField<RawGenericAddress, 5>,
+    sleep_status_reg: ExtendedField<RawGenericAddress, 5>,
+    hypervisor_vendor_id: ExtendedField<u64, 6>,
 }
 
 impl AcpiTable for Fadt {
@@ -96,11 +310,36 @@ impl AcpiTable for Fadt {
     }
 }
 
+unsafe impl CheckedTable for Fadt {
+    const SIGNATURE: Signature = Signature::FADT;
+
+    /// The length of the FADT in each revision. The fields after this are not accessed for FADTs of older
+    /// revisions.
+    fn min_length(revision: u8) -> usize {
+        match revision {
+            0..=1 => 116,
+            2..=4 => 244,
+            5 => 268,
+            _ => mem::size_of::<Fadt>(),
+        }
+    }
+}
+
 impl Fadt {
     pub fn validate(&self) -> Result<(), AcpiError> {
         self.header.validate(crate::sdt::Signature::FADT)
     }
 
+    /// The 32-bit and 64-bit DSDT addresses. The 64-bit address is only present in ACPI 2.0+ FADTs.
+    pub(crate) fn raw_dsdt_addresses(&self) -> (u32, Option<u64>) {
+        (self.dsdt_address, unsafe { self.x_dsdt_address.access(self.header.revision) })
+    }
+
+    /// The 32-bit and 64-bit FACS addresses. The 64-bit address is only present in ACPI 2.0+ FADTs.
+    pub(crate) fn raw_facs_addresses(&self) -> (u32, Option<u64>) {
+        (self.firmware_ctrl, unsafe { self.x_firmware_ctrl.access(self.header.revision) })
+    }
+
     pub fn dsdt_address(&self) -> Result<usize, AcpiError> {
         unsafe {
             self.x_dsdt_address
@@ -113,6 +352,16 @@ impl Fadt {
         }
     }
 
+    /// The physical address of the FACS, preferring the 64-bit field. Hardware-reduced platforms may not have a
+    /// FACS, in which case this is `None`.
+    pub fn facs_address(&self) -> Option<usize> {
+        unsafe { self.x_firmware_ctrl.access(self.header.revision) }
+            .filter(|&p| p != 0)
+            .or(Some(self.firmware_ctrl as u64))
+            .filter(|&p| p != 0)
+            .map(|p| p as usize)
+    }
+
     pub fn power_profile(&self) -> PowerProfile {
         match self.preferred_pm_profile {
             0 => PowerProfile::Unspecified,
@@ -128,26 +377,123 @@ impl Fadt {
         }
     }
 
+    pub fn feature_flags(&self) -> FixedFeatureFlags {
+        FixedFeatureFlags(self.flags)
+    }
+
+    pub fn hardware_model(&self) -> HardwareModel {
+        if self.feature_flags().hardware_reduced_acpi() {
+            HardwareModel::Reduced
+        } else {
+            HardwareModel::Full
+        }
+    }
+
+    /// The IA-PC boot architecture flags. These were added in ACPI 2.0, and so are empty for older FADTs.
+    pub fn iapc_boot_arch(&self) -> IaPcBootArchFlags {
+        IaPcBootArchFlags(if self.header.revision >= 2 { self.iapc_boot_arch } else { 0 })
+    }
+
+    /// The ARM boot architecture flags. These were added in ACPI 5.1, and so are empty for older FADTs.
+    pub fn arm_boot_arch(&self) -> ArmBootArchFlags {
+        ArmBootArchFlags(if self.header.revision >= 5 { self.arm_boot_arch } else { 0 })
+    }
+
+    pub fn pm1a_event_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        let x_field = unsafe { self.x_pm1a_event_block.access(self.header.revision) };
+        resolve_block(x_field, self.pm1a_event_block, self.pm1_event_length)
+    }
+
+    pub fn pm1b_event_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        let x_field = unsafe { self.x_pm1b_event_block.access(self.header.revision) };
+        resolve_block(x_field, self.pm1b_event_block, self.pm1_event_length)
+    }
+
+    pub fn pm1a_control_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        let x_field = unsafe { self.x_pm1a_control_block.access(self.header.revision) };
+        resolve_block(x_field, self.pm1a_control_block, self.pm1_control_length)
+    }
+
+    pub fn pm1b_control_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        let x_field = unsafe { self.x_pm1b_control_block.access(self.header.revision) };
+        resolve_block(x_field, self.pm1b_control_block, self.pm1_control_length)
+    }
+
+    pub fn pm2_control_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        let x_field = unsafe { self.x_pm2_control_block.access(self.header.revision) };
+        resolve_block(x_field, self.pm2_control_block, self.pm2_control_length)
+    }
+
     pub fn pm_timer_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
-        let raw = unsafe {
-            self.x_pm_timer_block.access(self.header().revision).or_else(|| {
-                if self.pm_timer_block != 0 {
-                    Some(RawGenericAddress {
-                        address_space: 1,
-                        bit_width: 0,
-                        bit_offset: 0,
-                        access_size: self.pm_timer_length,
-                        address: self.pm_timer_block.into(),
-                    })
-                } else {
-                    None
-                }
-            })
-        };
-
-        match raw {
-            Some(raw) => Ok(Some(GenericAddress::from_raw(raw)?)),
-            None => Ok(None),
+        let x_field = unsafe { self.x_pm_timer_block.access(self.header.revision) };
+        resolve_block(x_field, self.pm_timer_block, self.pm_timer_length)
+    }
+
+    pub fn gpe0_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        let x_field = unsafe { self.x_gpe0_block.access(self.header.revision) };
+        resolve_block(x_field, self.gpe0_block, self.gpe0_block_length)
+    }
+
+    pub fn gpe1_block(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        let x_field = unsafe { self.x_gpe1_block.access(self.header.revision) };
+        resolve_block(x_field, self.gpe1_block, self.gpe1_block_length)
+    }
+
+    pub fn gpe0_block_length(&self) -> u8 {
+        self.gpe0_block_length
+    }
+
+    pub fn gpe1_block_length(&self) -> u8 {
+        self.gpe1_block_length
+    }
+
+    /// The offset within the General-Purpose Events that the GPE1 block's events start at.
+    pub fn gpe1_base(&self) -> u8 {
+        self.gpe1_base
+    }
+
+    /// The register that can be written to in order to reset the system. This field was added in ACPI 2.0, and so
+    /// is not present in older FADTs.
+    pub fn reset_register(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        if self.header.revision < 2 {
+            return Ok(None);
         }
+
+        resolve_block(Some(self.reset_reg), 0, 0)
+    }
+
+    /// The value that should be written to the reset register to reset the system.
+    pub fn reset_value(&self) -> u8 {
+        self.reset_value
+    }
+
+    pub fn sleep_control_register(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        resolve_block(unsafe { self.sleep_control_reg.access(self.header.revision) }, 0, 0)
+    }
+
+    pub fn sleep_status_register(&self) -> Result<Option<GenericAddress>, AcpiError> {
+        resolve_block(unsafe { self.sleep_status_reg.access(self.header.revision) }, 0, 0)
+    }
+}
+
+/// Resolve one of the FADT's register blocks to a `GenericAddress`. The extended (`X_`) field is used if it's
+/// present and non-zero, and otherwise the legacy 32-bit field is used, which always describes a block of
+/// `legacy_length` bytes in I/O space. Blocks too long for their width to fit in the `bit_width` field (which is
+/// possible for the GPE blocks) are given a `bit_width` of `0`.
+fn resolve_block(
+    x_field: Option<RawGenericAddress>,
+    legacy_address: u32,
+    legacy_length: u8,
+) -> Result<Option<GenericAddress>, AcpiError> {
+    match x_field {
+        Some(raw) if { raw.address } != 0 => Ok(Some(GenericAddress::from_raw(raw)?)),
+        _ if legacy_address != 0 => Ok(Some(GenericAddress::from_raw(RawGenericAddress {
+            address_space: 1,
+            bit_width: legacy_length.checked_mul(8).unwrap_or(0),
+            bit_offset: 0,
+            access_size: 0,
+            address: legacy_address.into(),
+        })?)),
+        _ => Ok(None),
     }
 }
@@ -0,0 +1,364 @@
+use crate::{
+    sdt::{self, SdtHeader, Signature, SubtableFormat, SubtableIter},
+    AcpiError,
+    AcpiHandler,
+    AcpiTable,
+    AcpiTables,
+    CheckedTable,
+};
+use alloc::vec::Vec;
+use bit_field::BitField;
+use core::mem;
+
+#[derive(Debug)]
+pub enum HmatError {
+    /// Revision 1 of the HMAT (from ACPI 6.2) uses a different layout, which is not supported.
+    UnsupportedRevision,
+    /// A structure is too short to contain the lists it claims to have.
+    InvalidStructureLength,
+}
+
+/// Describes a memory proximity domain, and the initiator proximity domain (e.g. the processors) it is attached
+/// to.
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryProximityDomainAttributes {
+    pub memory_proximity_domain: u32,
+    /// The initiator proximity domain that the memory is attached to, if any. This is normally the initiator with
+    /// the best performance when accessing the memory.
+    pub attached_initiator_proximity_domain: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MemoryHierarchy {
+    /// The matrix describes accesses to the memory itself.
+    Memory,
+    /// The matrix describes accesses to the memory-side cache at the given level (from `1` to `3`).
+    MemorySideCache(u8),
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LocalityDataType {
+    AccessLatency,
+    ReadLatency,
+    WriteLatency,
+    AccessBandwidth,
+    ReadBandwidth,
+    WriteBandwidth,
+    Reserved(u8),
+}
+
+impl LocalityDataType {
+    pub fn is_latency(&self) -> bool {
+        matches!(
+            self,
+            LocalityDataType::AccessLatency | LocalityDataType::ReadLatency | LocalityDataType::WriteLatency
+        )
+    }
+
+    pub fn is_bandwidth(&self) -> bool {
+        matches!(
+            self,
+            LocalityDataType::AccessBandwidth | LocalityDataType::ReadBandwidth | LocalityDataType::WriteBandwidth
+        )
+    }
+}
+
+/// A matrix of the latency or bandwidth between a set of initiator proximity domains and a set of target
+/// proximity domains.
+#[derive(Clone, Debug)]
+pub struct LocalityMatrix {
+    pub memory_hierarchy: MemoryHierarchy,
+    pub data_type: LocalityDataType,
+    pub initiator_proximity_domains: Vec<u32>,
+    pub target_proximity_domains: Vec<u32>,
+    entry_base_unit: u64,
+    entries: Vec<u16>,
+}
+
+impl LocalityMatrix {
+    /// Get the latency (in picoseconds) or bandwidth (in MB/s) from the given initiator proximity domain to the
+    /// given target proximity domain. This returns `None` if either domain is not in the matrix, or the HMAT does
+    /// not provide a value for the pair.
+    pub fn get(&self, initiator_proximity_domain: u32, target_proximity_domain: u32) -> Option<u64> {
+        let initiator =
+            self.initiator_proximity_domains.iter().position(|&domain| domain == initiator_proximity_domain)?;
+        let target = self.target_proximity_domains.iter().position(|&domain| domain == target_proximity_domain)?;
+
+        match self.entries[initiator * self.target_proximity_domains.len() + target] {
+            0 | 0xffff => None,
+            entry => Some(entry as u64 * self.entry_base_unit),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CacheAssociativity {
+    None,
+    DirectMapped,
+    /// The cache uses a complex (e.g. hashed) indexing scheme.
+    Complex,
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CacheWritePolicy {
+    None,
+    WriteBack,
+    WriteThrough,
+    Reserved(u8),
+}
+
+/// Describes a memory-side cache, which caches accesses to the memory of a memory proximity domain (e.g. DRAM
+/// acting as a cache for slower persistent memory).
+#[derive(Clone, Debug)]
+pub struct MemorySideCache {
+    pub memory_proximity_domain: u32,
+    /// The size of the cache, in bytes.
+    pub size: u64,
+    pub total_cache_levels: u8,
+    pub cache_level: u8,
+    pub associativity: CacheAssociativity,
+    pub write_policy: CacheWritePolicy,
+    /// The size of a cache line, in bytes.
+    pub line_size: u16,
+    /// Handles of the SMBIOS Memory Device structures that make up the cache.
+    pub smbios_handles: Vec<u16>,
+}
+
+/// Information about the performance of the memory of the system, parsed from the Heterogeneous Memory
+/// Attribute Table (HMAT). Proximity domains are the same as those used by the SRAT.
+#[derive(Clone, Debug)]
+pub struct HmatInfo {
+    pub memory_proximity_domains: Vec<MemoryProximityDomainAttributes>,
+    pub locality_matrices: Vec<LocalityMatrix>,
+    pub memory_side_caches: Vec<MemorySideCache>,
+}
+
+impl HmatInfo {
+    pub fn new<H>(tables: &AcpiTables<H>) -> Result<HmatInfo, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let hmat = tables.get_table::<Hmat>()?.ok_or(AcpiError::TableMissing(Signature::HMAT))?;
+
+        if hmat.header.revision < 2 {
+            return Err(AcpiError::InvalidHmat(HmatError::UnsupportedRevision));
+        }
+
+        let mut memory_proximity_domains = Vec::new();
+        let mut locality_matrices = Vec::new();
+        let mut memory_side_caches = Vec::new();
+
+        for entry in hmat.entries() {
+            match entry {
+                HmatEntry::MemoryProximityDomainAttributes(entry) => {
+                    memory_proximity_domains.push(MemoryProximityDomainAttributes {
+                        memory_proximity_domain: entry.memory_proximity_domain,
+                        attached_initiator_proximity_domain: if { entry.flags }.get_bit(0) {
+                            Some(entry.attached_initiator_proximity_domain)
+                        } else {
+                            None
+                        },
+                    });
+                }
+
+                HmatEntry::SystemLocality(entry, data) => {
+                    let num_initiators = entry.num_initiator_proximity_domains as usize;
+                    let num_targets = entry.num_target_proximity_domains as usize;
+                    let num_entries = num_initiators.checked_mul(num_targets);
+                    let words = num_entries
+                        .and_then(|entries| (num_initiators + num_targets).checked_mul(2)?.checked_add(entries))
+                        .filter(|&words| words.saturating_mul(2) <= data.len())
+                        .ok_or(AcpiError::InvalidHmat(HmatError::InvalidStructureLength))?;
+
+                    let bytes = &data[..words * 2];
+                    let (domains, entries) = bytes.split_at((num_initiators + num_targets) * 4);
+                    let mut domains = domains
+                        .chunks_exact(4)
+                        .map(|domain| u32::from_le_bytes([domain[0], domain[1], domain[2], domain[3]]));
+
+                    locality_matrices.push(LocalityMatrix {
+                        memory_hierarchy: match entry.flags.get_bits(0..4) {
+                            0 => MemoryHierarchy::Memory,
+                            level @ 1..=3 => MemoryHierarchy::MemorySideCache(level),
+                            other => MemoryHierarchy::Reserved(other),
+                        },
+                        data_type: match entry.data_type {
+                            0 => LocalityDataType::AccessLatency,
+                            1 => LocalityDataType::ReadLatency,
+                            2 => LocalityDataType::WriteLatency,
+                            3 => LocalityDataType::AccessBandwidth,
+                            4 => LocalityDataType::ReadBandwidth,
+                            5 => LocalityDataType::WriteBandwidth,
+                            other => LocalityDataType::Reserved(other),
+                        },
+                        initiator_proximity_domains: domains.by_ref().take(num_initiators).collect(),
+                        target_proximity_domains: domains.collect(),
+                        entry_base_unit: entry.entry_base_unit,
+                        entries: entries
+                            .chunks_exact(2)
+                            .map(|entry| u16::from_le_bytes([entry[0], entry[1]]))
+                            .collect(),
+                    });
+                }
+
+                HmatEntry::MemorySideCache(entry, data) => {
+                    let num_handles = entry.num_smbios_handles as usize;
+                    if num_handles * 2 > data.len() {
+                        return Err(AcpiError::InvalidHmat(HmatError::InvalidStructureLength));
+                    }
+
+                    let handles = &data[..num_handles * 2];
+                    let attributes = entry.cache_attributes;
+
+                    memory_side_caches.push(MemorySideCache {
+                        memory_proximity_domain: entry.memory_proximity_domain,
+                        size: entry.memory_side_cache_size,
+                        total_cache_levels: attributes.get_bits(0..4) as u8,
+                        cache_level: attributes.get_bits(4..8) as u8,
+                        associativity: match attributes.get_bits(8..12) as u8 {
+                            0 => CacheAssociativity::None,
+                            1 => CacheAssociativity::DirectMapped,
+                            2 => CacheAssociativity::Complex,
+                            other => CacheAssociativity::Reserved(other),
+                        },
+                        write_policy: match attributes.get_bits(12..16) as u8 {
+                            0 => CacheWritePolicy::None,
+                            1 => CacheWritePolicy::WriteBack,
+                            2 => CacheWritePolicy::WriteThrough,
+                            other => CacheWritePolicy::Reserved(other),
+                        },
+                        line_size: attributes.get_bits(16..32) as u16,
+                        smbios_handles: handles
+                            .chunks_exact(2)
+                            .map(|handle| u16::from_le_bytes([handle[0], handle[1]]))
+                            .collect(),
+                    });
+                }
+            }
+        }
+
+        Ok(HmatInfo { memory_proximity_domains, locality_matrices, memory_side_caches })
+    }
+
+    /// Find the matrix that describes the given type of data for accesses to memory (rather than to memory-side
+    /// caches).
+    pub fn memory_locality(&self, data_type: LocalityDataType) -> Option<&LocalityMatrix> {
+        self.locality_matrices
+            .iter()
+            .find(|matrix| matrix.memory_hierarchy == MemoryHierarchy::Memory && matrix.data_type == data_type)
+    }
+}
+
+#[repr(C, packed)]
+pub(crate) struct Hmat {
+    header: SdtHeader,
+    _reserved: u32,
+    // Followed by a number of variable-length structures
+}
+
+impl AcpiTable for Hmat {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+unsafe impl CheckedTable for Hmat {
+    const SIGNATURE: Signature = Signature::HMAT;
+}
+
+impl Hmat {
+    fn entries(&self) -> HmatEntryIter<'_> {
+        HmatEntryIter { subtables: SubtableIter::new(unsafe { sdt::table_contents(self) }, SubtableFormat::Long) }
+    }
+}
+
+struct HmatEntryIter<'a> {
+    subtables: SubtableIter<'a>,
+}
+
+/// A structure of the HMAT, along with the variable-length data that follows its fixed fields.
+enum HmatEntry<'a> {
+    MemoryProximityDomainAttributes(&'a MemoryProximityDomainAttributesEntry),
+    SystemLocality(&'a SystemLocalityEntry, &'a [u8]),
+    MemorySideCache(&'a MemorySideCacheEntry, &'a [u8]),
+}
+
+impl<'a> Iterator for HmatEntryIter<'a> {
+    type Item = HmatEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        /*
+         * Stop at a malformed structure, as there's no way to find the next one.
+         */
+        while let Some(Ok(subtable)) = self.subtables.next() {
+            let entry = match subtable.subtable_type {
+                0x0 => unsafe { subtable.get::<MemoryProximityDomainAttributesEntry>() }
+                    .map(HmatEntry::MemoryProximityDomainAttributes),
+                0x1 => unsafe { subtable.get::<SystemLocalityEntry>() }.map(|entry| {
+                    HmatEntry::SystemLocality(entry, &subtable.bytes[mem::size_of::<SystemLocalityEntry>()..])
+                }),
+                0x2 => unsafe { subtable.get::<MemorySideCacheEntry>() }.map(|entry| {
+                    HmatEntry::MemorySideCache(entry, &subtable.bytes[mem::size_of::<MemorySideCacheEntry>()..])
+                }),
+                _ => None,
+            };
+
+            /*
+             * Skip any entries we don't understand, or that are too short to be valid.
+             */
+            if let Some(entry) = entry {
+                return Some(entry);
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct EntryHeader {
+    entry_type: u16,
+    _reserved: u16,
+    length: u32,
+}
+
+#[repr(C, packed)]
+struct MemoryProximityDomainAttributesEntry {
+    header: EntryHeader,
+    flags: u16,
+    _reserved1: u16,
+    attached_initiator_proximity_domain: u32,
+    memory_proximity_domain: u32,
+    _reserved2: u32,
+    _reserved3: u64,
+    _reserved4: u64,
+}
+
+#[repr(C, packed)]
+struct SystemLocalityEntry {
+    header: EntryHeader,
+    flags: u8,
+    data_type: u8,
+    min_transfer_size: u8,
+    _reserved1: u8,
+    num_initiator_proximity_domains: u32,
+    num_target_proximity_domains: u32,
+    _reserved2: u32,
+    entry_base_unit: u64,
+    // Followed by the initiator and target proximity domain lists, and then the matrix of entries
+}
+
+#[repr(C, packed)]
+struct MemorySideCacheEntry {
+    header: EntryHeader,
+    memory_proximity_domain: u32,
+    _reserved1: u32,
+    memory_side_cache_size: u64,
+    cache_attributes: u32,
+    _reserved2: u16,
+    num_smbios_handles: u16,
+    // Followed by `num_smbios_handles` SMBIOS handles
+}
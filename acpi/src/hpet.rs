@@ -1,7 +1,15 @@
-use crate::{platform::address::RawGenericAddress, sdt::SdtHeader, AcpiError, AcpiHandler, AcpiTable, AcpiTables};
+use crate::{
+    platform::address::RawGenericAddress,
+    sdt::{SdtHeader, Signature},
+    AcpiError,
+    AcpiHandler,
+    AcpiTable,
+    AcpiTables,
+    CheckedTable,
+};
 use bit_field::BitField;
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum PageProtection {
     None,
     /// Access to the adjacent 3KB to the base address will not generate a fault.
@@ -27,11 +35,7 @@ impl HpetInfo {
     where
         H: AcpiHandler,
     {
-        let hpet = unsafe {
-            tables
-                .get_sdt::<HpetTable>(crate::sdt::Signature::HPET)?
-                .ok_or(AcpiError::TableMissing(crate::sdt::Signature::HPET))?
-        };
+        let hpet = tables.get_table::<HpetTable>()?.ok_or(AcpiError::TableMissing(Signature::HPET))?;
 
         // Make sure the HPET's in system memory
         assert_eq!(hpet.base_address.address_space, 0);
@@ -67,3 +71,7 @@ impl AcpiTable for HpetTable {
         &self.header
     }
 }
+
+unsafe impl CheckedTable for HpetTable {
+    const SIGNATURE: Signature = Signature::HPET;
+}
@@ -0,0 +1,448 @@
+//! Support for constructing an `AcpiTables` from tables that have been loaded into memory, rather than found in
+//! physical memory. This is useful for userspace tools and tests, which may want to parse tables read from
+//! `/sys/firmware/acpi/tables`, from the binary output of `acpidump`, or from a directory of tables dumped by
+//! `acpi-dumper`.
+//!
+//! The tables are placed at made-up physical addresses, which the [`InMemoryHandler`] translates back into the
+//! memory the tables were copied into. The pointers to the DSDT and FACS in the FADT are rewritten to point at the
+//! copies of those tables, so everything that works on an `AcpiTables` found by the firmware also works here.
+
+use crate::{
+    facs::Facs,
+    sdt::SdtHeader,
+    AcpiError,
+    AcpiHandler,
+    AcpiTables,
+    AmlTable,
+    PhysicalMapping,
+    Sdt,
+    TableInfo,
+    ValidationPolicy,
+};
+use alloc::{collections::BTreeMap, rc::Rc, vec::Vec};
+use core::{cell::UnsafeCell, mem, ptr, ptr::NonNull};
+use std::{fs, io, path::Path};
+
+/// The made-up physical address that the first table is placed at. Each table starts on a new 4KiB page after
+/// this.
+const BASE_ADDRESS: usize = 0x1000;
+const PAGE_SIZE: usize = 0x1000;
+
+const FADT_FIRMWARE_CTRL_OFFSET: usize = 36;
+const FADT_DSDT_OFFSET: usize = 40;
+const FADT_X_FIRMWARE_CTRL_OFFSET: usize = 132;
+const FADT_X_DSDT_OFFSET: usize = 140;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    /// A table was too short to contain its header, or its length did not match the length in its header.
+    InvalidLength,
+    Acpi(AcpiError),
+}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> LoadError {
+        LoadError::Io(err)
+    }
+}
+
+impl From<AcpiError> for LoadError {
+    fn from(err: AcpiError) -> LoadError {
+        LoadError::Acpi(err)
+    }
+}
+
+struct Region {
+    physical_address: usize,
+    length: usize,
+    /// The contents of the table. This is stored as `u64`s so that the table is suitably aligned to be accessed as
+    /// any of the table types, and in `UnsafeCell`s as some tables (such as the FACS) can be written to.
+    data: Vec<UnsafeCell<u64>>,
+}
+
+/// An `AcpiHandler` that "maps" the made-up physical addresses of tables loaded into memory by translating them
+/// into pointers to the tables' contents. Mapping any region that is not entirely within one of the tables will
+/// panic.
+#[derive(Clone)]
+pub struct InMemoryHandler {
+    regions: Rc<Vec<Region>>,
+}
+
+impl AcpiHandler for InMemoryHandler {
+    unsafe fn map_physical_region<T>(&self, physical_address: usize, size: usize) -> PhysicalMapping<Self, T> {
+        let region = self
+            .regions
+            .iter()
+            .find(|region| {
+                physical_address >= region.physical_address
+                    && physical_address
+                        .checked_add(size)
+                        .is_some_and(|end| end <= region.physical_address + region.length)
+            })
+            .unwrap_or_else(|| panic!("Tried to map {:#x}, which is not part of any table", physical_address));
+
+        let offset = physical_address - region.physical_address;
+        let virtual_start =
+            unsafe { (UnsafeCell::raw_get(region.data.as_ptr()) as *mut u8).add(offset) } as *mut T;
+
+        PhysicalMapping {
+            physical_start: physical_address,
+            virtual_start: NonNull::new(virtual_start).unwrap(),
+            region_length: size,
+            mapped_length: size,
+            handler: self.clone(),
+        }
+    }
+
+    fn unmap_physical_region<T>(&self, _region: &PhysicalMapping<Self, T>) {}
+}
+
+impl AcpiTables<InMemoryHandler> {
+    /// Construct an `AcpiTables` from a list of raw tables, each of which should be a complete table including its
+    /// header. Each table is validated in the same way as tables found in physical memory. The FACS, which does
+    /// not have a standard header, may also be included. The RSDP, RSDT, and XSDT are not needed, and are ignored
+    /// if they are included.
+    pub fn from_raw_tables<I, T>(tables: I) -> Result<AcpiTables<InMemoryHandler>, LoadError>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut revision = None;
+        let mut has_xsdt = false;
+        let mut regions = Vec::new();
+        let mut next_address = BASE_ADDRESS;
+
+        for table in tables {
+            let table = table.as_ref();
+
+            if table.starts_with(b"RSD PTR ") {
+                revision = Some(*table.get(15).ok_or(LoadError::InvalidLength)?);
+                continue;
+            }
+            if table.len() < 8 {
+                return Err(LoadError::InvalidLength);
+            }
+
+            match &table[0..4] {
+                b"RSDT" => continue,
+                b"XSDT" => {
+                    has_xsdt = true;
+                    continue;
+                }
+                b"FACS" => {
+                    if table.len() < Facs::MIN_LENGTH as usize {
+                        return Err(LoadError::InvalidLength);
+                    }
+                }
+                signature => {
+                    validate_length(table)?;
+
+                    /*
+                     * The FADT's pointers are patched below, so it must be long enough to hold them.
+                     */
+                    if signature == b"FACP" && table.len() < FADT_DSDT_OFFSET + 4 {
+                        return Err(LoadError::InvalidLength);
+                    }
+
+                    /*
+                     * Validate the table as we were given it, before the FADT is patched and its checksum is
+                     * recalculated.
+                     */
+                    let header = unsafe { &*(table.as_ptr() as *const SdtHeader) };
+                    header.validate(header.signature)?;
+                }
+            }
+
+            let mut data: Vec<UnsafeCell<u64>> =
+                (0..table.len().div_ceil(8)).map(|_| UnsafeCell::new(0)).collect();
+            unsafe {
+                ptr::copy_nonoverlapping(table.as_ptr(), data.as_mut_ptr() as *mut u8, table.len());
+            }
+
+            regions.push(Region { physical_address: next_address, length: table.len(), data });
+            next_address = align_up(next_address + table.len(), PAGE_SIZE);
+        }
+
+        let find = |signature: &[u8; 4]| {
+            regions
+                .iter()
+                .find(|region| &region_bytes(region)[0..4] == signature)
+                .map(|region| region.physical_address)
+        };
+        let dsdt_address = find(b"DSDT");
+        let facs_address = find(b"FACS");
+
+        /*
+         * Point the FADT at our copies of the DSDT and FACS, instead of wherever they were in physical memory. The
+         * tables have all been validated by this point, so this can't hide a bad checksum.
+         */
+        for region in regions.iter_mut().filter(|region| &region_bytes(region)[0..4] == b"FACP") {
+            patch_fadt(region, dsdt_address, facs_address);
+        }
+
+        let handler = InMemoryHandler { regions: Rc::new(regions) };
+        let mut sdts = BTreeMap::new();
+        let mut dsdt = None;
+        let mut ssdts = Vec::new();
+        let mut all_tables = Vec::new();
+
+        for region in handler.regions.iter().filter(|region| &region_bytes(region)[0..4] != b"FACS") {
+            let physical_address = region.physical_address;
+            let header = crate::sdt::peek_at_sdt_header(&handler, physical_address);
+
+            match &region_bytes(region)[0..4] {
+                b"DSDT" => {
+                    dsdt = Some(AmlTable::new(physical_address, header.length));
+                    continue;
+                }
+                b"SSDT" => ssdts.push(AmlTable::new(physical_address, header.length)),
+                _ => {
+                    sdts.insert(
+                        header.signature,
+                        Sdt { physical_address, length: header.length, validated: true },
+                    );
+                }
+            }
+
+            all_tables.push(TableInfo { physical_address, length: header.length, header });
+        }
+
+        Ok(AcpiTables {
+            revision: revision.unwrap_or(if has_xsdt { 2 } else { 0 }),
+            sdts,
+            dsdt,
+            ssdts,
+            all_tables,
+            root_table: None,
+            validation_policy: ValidationPolicy::Strict,
+            handler,
+        })
+    }
+
+    /// Construct an `AcpiTables` from a blob of tables placed one after another, such as the binary output of
+    /// `acpidump`. See [`AcpiTables::from_raw_tables`] for details.
+    pub fn from_blob(blob: &[u8]) -> Result<AcpiTables<InMemoryHandler>, LoadError> {
+        let mut tables = Vec::new();
+        let mut remaining = blob;
+
+        while !remaining.is_empty() {
+            let length = if remaining.starts_with(b"RSD PTR ") {
+                /*
+                 * The RSDP doesn't have a standard header. ACPI 1.0 RSDPs are 20 bytes long, while later revisions
+                 * include their length.
+                 */
+                match remaining.get(15) {
+                    Some(0) => 20,
+                    Some(_) => read_u32(remaining, 20)? as usize,
+                    None => return Err(LoadError::InvalidLength),
+                }
+            } else {
+                read_u32(remaining, 4)? as usize
+            };
+
+            if length < 8 || length > remaining.len() {
+                return Err(LoadError::InvalidLength);
+            }
+
+            let (table, rest) = remaining.split_at(length);
+            tables.push(table);
+            remaining = rest;
+        }
+
+        Self::from_raw_tables(tables)
+    }
+
+    /// Construct an `AcpiTables` from a directory of files that each contain a single table, such as
+    /// `/sys/firmware/acpi/tables`, or a directory dumped by `acpi-dumper`. Files without an extension, or with
+    /// the `.bin` or `.dat` extensions, are loaded, in order of their names. Other files and any subdirectories
+    /// are ignored. See [`AcpiTables::from_raw_tables`] for details.
+    pub fn from_directory<P>(path: P) -> Result<AcpiTables<InMemoryHandler>, LoadError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+
+            let path = entry.path();
+            match path.extension().and_then(|extension| extension.to_str()) {
+                None | Some("bin") | Some("dat") => paths.push(path),
+                Some(_) => continue,
+            }
+        }
+        paths.sort();
+
+        let tables = paths.iter().map(fs::read).collect::<Result<Vec<_>, _>>()?;
+        Self::from_raw_tables(tables)
+    }
+}
+
+fn validate_length(table: &[u8]) -> Result<(), LoadError> {
+    if table.len() < mem::size_of::<SdtHeader>() || read_u32(table, 4)? as usize != table.len() {
+        return Err(LoadError::InvalidLength);
+    }
+
+    Ok(())
+}
+
+fn patch_fadt(region: &mut Region, dsdt_address: Option<usize>, facs_address: Option<usize>) {
+    let bytes = unsafe { core::slice::from_raw_parts_mut(region.data.as_mut_ptr() as *mut u8, region.length) };
+    let dsdt_address = dsdt_address.unwrap_or(0);
+    let facs_address = facs_address.unwrap_or(0);
+
+    bytes[FADT_FIRMWARE_CTRL_OFFSET..(FADT_FIRMWARE_CTRL_OFFSET + 4)]
+        .copy_from_slice(&(facs_address as u32).to_le_bytes());
+    bytes[FADT_DSDT_OFFSET..(FADT_DSDT_OFFSET + 4)].copy_from_slice(&(dsdt_address as u32).to_le_bytes());
+    if bytes.len() >= FADT_X_DSDT_OFFSET + 8 {
+        bytes[FADT_X_FIRMWARE_CTRL_OFFSET..(FADT_X_FIRMWARE_CTRL_OFFSET + 8)]
+            .copy_from_slice(&(facs_address as u64).to_le_bytes());
+        bytes[FADT_X_DSDT_OFFSET..(FADT_X_DSDT_OFFSET + 8)].copy_from_slice(&(dsdt_address as u64).to_le_bytes());
+    }
+
+    /*
+     * Fix up the checksum, which lives in byte 9 of the header.
+     */
+    bytes[9] = 0;
+    let sum = bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+    bytes[9] = 0u8.wrapping_sub(sum);
+}
+
+fn region_bytes(region: &Region) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(region.data.as_ptr() as *const u8, region.length) }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, LoadError> {
+    let mut value = [0; 4];
+    value.copy_from_slice(bytes.get(offset..(offset + 4)).ok_or(LoadError::InvalidLength)?);
+    Ok(u32::from_le_bytes(value))
+}
+
+fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{platform::PlatformInfo, sdt::Signature, HpetInfo, PageProtection, PowerProfile};
+    use alloc::vec;
+
+    /// Build a table with a correct length and checksum from its signature, revision, and the contents that
+    /// follow the header.
+    fn table(signature: &[u8; 4], revision: u8, contents: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0; mem::size_of::<SdtHeader>()];
+        bytes[0..4].copy_from_slice(signature);
+        bytes[8] = revision;
+        bytes[10..16].copy_from_slice(b"RUST  ");
+        bytes[16..24].copy_from_slice(b"RUSTACPI");
+        bytes.extend_from_slice(contents);
+
+        let length = bytes.len() as u32;
+        bytes[4..8].copy_from_slice(&length.to_le_bytes());
+        let sum = bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+        bytes[9] = 0u8.wrapping_sub(sum);
+        bytes
+    }
+
+    /// A revision 6 FADT, as found in physical memory, with DSDT and X_DSDT pointers that are not valid once the
+    /// tables have been loaded.
+    fn fadt() -> Vec<u8> {
+        let mut contents = vec![0; 276 - mem::size_of::<SdtHeader>()];
+        contents[4..8].copy_from_slice(&0x7fe0_0000u32.to_le_bytes());
+        contents[9] = 4;
+        contents[104..112].copy_from_slice(&0x7fe0_0000u64.to_le_bytes());
+        table(b"FACP", 6, &contents)
+    }
+
+    fn hpet() -> Vec<u8> {
+        let mut contents = Vec::new();
+        contents.extend_from_slice(&0x8086_a201u32.to_le_bytes());
+        contents.extend_from_slice(&[0, 64, 0, 0]);
+        contents.extend_from_slice(&0xfed0_0000u64.to_le_bytes());
+        contents.extend_from_slice(&[0, 0x80, 0x00, 1]);
+        table(b"HPET", 1, &contents)
+    }
+
+    fn madt() -> Vec<u8> {
+        let mut contents = Vec::new();
+        contents.extend_from_slice(&0xfee0_0000u32.to_le_bytes());
+        contents.extend_from_slice(&0u32.to_le_bytes());
+        contents.extend_from_slice(&[0x0, 8, 0, 0, 1, 0, 0, 0]);
+        table(b"APIC", 5, &contents)
+    }
+
+    fn dsdt() -> Vec<u8> {
+        table(b"DSDT", 2, &[0x10, 0x05, 0x5c, 0x5f, 0x53, 0x42])
+    }
+
+    fn fadt_bytes(tables: &AcpiTables<InMemoryHandler>) -> &[u8] {
+        let region = tables.handler.regions.iter().find(|region| &region_bytes(region)[0..4] == b"FACP").unwrap();
+        region_bytes(region)
+    }
+
+    #[test]
+    fn from_blob() {
+        let blob = [fadt(), hpet(), madt(), dsdt()].concat();
+        let tables = AcpiTables::from_blob(&blob).unwrap();
+
+        let platform_info = PlatformInfo::new(&tables).unwrap();
+        assert_eq!(platform_info.power_profile, PowerProfile::EnterpriseServer);
+        let processor_info = platform_info.processor_info.unwrap();
+        assert_eq!(processor_info.boot_processor.processor_uid, 0);
+        assert!(processor_info.application_processors.is_empty());
+
+        let hpet_info = HpetInfo::new(&tables).unwrap();
+        assert_eq!(hpet_info.event_timer_block_id, 0x8086_a201);
+        assert_eq!(hpet_info.base_address, 0xfed0_0000);
+        assert_eq!(hpet_info.clock_tick_unit, 0x80);
+        assert_eq!(hpet_info.page_protection, PageProtection::Protected4K);
+    }
+
+    #[test]
+    fn fadt_points_at_loaded_dsdt() {
+        let tables = AcpiTables::from_raw_tables(vec![fadt(), dsdt()]).unwrap();
+        let dsdt = tables.dsdt.as_ref().unwrap();
+        let dsdt_address = dsdt.address - mem::size_of::<SdtHeader>();
+
+        let fadt = fadt_bytes(&tables);
+        assert_eq!(read_u32(fadt, FADT_DSDT_OFFSET).unwrap() as usize, dsdt_address);
+        let mut x_dsdt = [0; 8];
+        x_dsdt.copy_from_slice(&fadt[FADT_X_DSDT_OFFSET..(FADT_X_DSDT_OFFSET + 8)]);
+        assert_eq!(u64::from_le_bytes(x_dsdt) as usize, dsdt_address);
+        assert_eq!(
+            tables.get_table::<crate::fadt::Fadt>().unwrap().unwrap().dsdt_address().unwrap(),
+            dsdt_address
+        );
+
+        let header = crate::sdt::peek_at_sdt_header(&tables.handler, dsdt_address);
+        assert_eq!(header.signature, Signature::DSDT);
+    }
+
+    #[test]
+    fn bad_fadt_checksum() {
+        let mut fadt = fadt();
+        fadt[9] = fadt[9].wrapping_add(1);
+
+        /*
+         * The FADT would be re-checksummed when it is patched, so this must be caught before then.
+         */
+        let blob = [fadt, dsdt()].concat();
+        assert!(matches!(
+            AcpiTables::from_blob(&blob),
+            Err(LoadError::Acpi(AcpiError::SdtInvalidChecksum(Signature::FADT)))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "not part of any table")]
+    fn map_outside_tables() {
+        let tables = AcpiTables::from_raw_tables(vec![dsdt()]).unwrap();
+        unsafe {
+            tables.handler.map_physical_region::<u8>(usize::MAX, 2);
+        }
+    }
+}
@@ -21,13 +21,25 @@
 //! * Use `AcpiTables::from_rsdt` if you have the physical address of the RSDT/XSDT
 //! * Use `AcpiTables::search_for_rsdp_bios` if you don't have the address of either, but **you know you are
 //! running on BIOS, not UEFI**
+//! * Use `AcpiTables::search_for_rsdp_uefi` if you are running on UEFI, and have the UEFI Configuration Table
+//! * Use `AcpiTables::search_for_rsdp_multiboot2` or `AcpiTables::search_for_rsdp_linux_boot_params` if you were
+//!   booted by a Multiboot2 bootloader, or using the Linux boot protocol
 //! * Use `AcpiTables::from_tables_direct` if you are using the library in an unusual setting, such as in usermode,
 //!   and have a custom method to enumerate and access the tables.
+//! * With the `std` feature enabled, use `AcpiTables::from_raw_tables`, `AcpiTables::from_blob`, or
+//!   `AcpiTables::from_directory` if you have copies of the tables in memory or on disk.
+//!
+//! With the `builder` feature enabled, the `builder` module can also be used to create tables, for example to
+//! provide them to a virtual machine.
 //!
 //! `AcpiTables` stores the addresses of all of the tables detected on a platform. The SDTs are parsed by this
 //! library, or can be accessed directly with `from_sdt`, while the `DSDT` and any `SSDTs` should be parsed with
 //! `aml`.
 //!
+//! Tables with a type in this crate can be accessed safely with `AcpiTables::get_table`, which checks that the
+//! table is long enough for its type. The variable-length structures within a table can be walked with
+//! [`sdt::subtables`], which checks each of them against the length of the table.
+//!
 //! To gather information out of the static tables, a few of the types you should take a look at are:
 //!    - [`PlatformInfo`](crate::platform::PlatformInfo) parses the FADT and MADT to create a nice view of the
 //!      processor topology and interrupt controllers on `x86_64`, and the interrupt controllers on other platforms.
@@ -36,6 +48,16 @@
 //!      Precision Event Timer.
 //!    - [`PciConfigRegions`](crate::mcfg::PciConfigRegions) parses the MCFG and tells you how PCIe configuration
 //!      space is mapped into physical memory.
+//!    - [`SratInfo`](crate::srat::SratInfo) parses the SRAT and tells you which proximity domain (NUMA node) each
+//!      processor and range of memory belongs to.
+//!    - [`NumaDistances`](crate::slit::NumaDistances) parses the SLIT and tells you the relative distances between
+//!      those proximity domains.
+//!    - [`ProcessorTopology`](crate::pptt::ProcessorTopology) parses the PPTT and tells you how the processors are
+//!      grouped into packages, clusters, cores and threads, and which caches they share.
+//!    - [`HmatInfo`](crate::hmat::HmatInfo) parses the HMAT and tells you the latency and bandwidth of accesses
+//!      between proximity domains, which is useful on systems with tiered memory.
+//!    - [`Facs`](crate::facs::Facs) is found through the FADT, and contains the firmware waking vectors and the
+//!      global lock. `AcpiTables::facs` maps it for you.
 
 #![no_std]
 #![feature(const_generics, unsafe_block_in_unsafe_fn)]
@@ -43,32 +65,59 @@
 
 extern crate alloc;
 #[cfg_attr(test, macro_use)]
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 extern crate std;
 
+#[cfg(feature = "builder")]
+pub mod builder;
+pub mod facs;
 mod fadt;
+pub mod hmat;
 mod hpet;
-mod madt;
+#[cfg(feature = "std")]
+pub mod in_memory;
+pub mod madt;
 mod mcfg;
 pub mod platform;
+pub mod pptt;
 pub mod sdt;
+pub mod slit;
+pub mod srat;
+pub mod validation;
 
 pub use crate::{
-    fadt::PowerProfile,
-    hpet::HpetInfo,
+    facs::Facs,
+    fadt::{
+        ArmBootArchFlags,
+        FixedFeatureFlags,
+        HardwareModel,
+        IaPcBootArchFlags,
+        PersistentCpuCaches,
+        PowerProfile,
+    },
+    hmat::{HmatError, HmatInfo},
+    hpet::{HpetInfo, PageProtection},
     madt::MadtError,
     mcfg::PciConfigRegions,
     platform::{InterruptModel, PlatformInfo},
+    pptt::{PpttError, ProcessorTopology},
+    slit::{NumaDistances, SlitError},
+    srat::SratInfo,
+    validation::{ValidationPolicy, ValidationReport},
 };
 pub use rsdp::{
     handler::{AcpiHandler, PhysicalMapping},
+    uefi::ConfigurationTableEntry,
     RsdpError,
 };
 
-use crate::sdt::{SdtHeader, Signature};
+use crate::{
+    platform::address::AddressSpace,
+    sdt::{SdtHeader, Signature},
+};
 use alloc::{collections::BTreeMap, vec::Vec};
-use core::mem;
-use log::trace;
+use core::{convert::TryFrom, mem};
+use log::{trace, warn};
 use rsdp::Rsdp;
 
 #[derive(Debug)]
@@ -79,11 +128,22 @@ pub enum AcpiError {
     SdtInvalidOemId(Signature),
     SdtInvalidTableId(Signature),
     SdtInvalidChecksum(Signature),
+    /// The table is too short to contain its header, or its length is not a whole number of entries.
+    SdtInvalidLength(Signature),
 
     TableMissing(Signature),
     InvalidDsdtAddress,
+    InvalidFacsLength,
     InvalidMadt(MadtError),
+    InvalidSlit(SlitError),
+    InvalidPptt(PpttError),
+    InvalidHmat(HmatError),
     InvalidGenericAddress,
+    UnsupportedAddressSpace(AddressSpace),
+    AddressTooLarge(u64),
+    TableTooShort(Signature),
+    InvalidSubtableLength,
+    ResetUnsupported,
 }
 
 pub struct AcpiTables<H>
@@ -95,9 +155,36 @@ where
     pub sdts: BTreeMap<sdt::Signature, Sdt>,
     pub dsdt: Option<AmlTable>,
     pub ssdts: Vec<AmlTable>,
+    /// Every table listed by the RSDT/XSDT, in the order they're listed. Unlike `sdts`, this includes all of the
+    /// tables that share a signature.
+    all_tables: Vec<TableInfo>,
+    root_table: Option<RootTable>,
+    validation_policy: ValidationPolicy,
     handler: H,
 }
 
+/// The root tables, which list the addresses of all of the other tables.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RootTable {
+    /// The Root System Description Table, which contains 32-bit addresses. This is used on ACPI 1.0 platforms.
+    Rsdt,
+    /// The Extended System Description Table, which contains 64-bit addresses. This should be used on ACPI 2.0+
+    /// platforms.
+    Xsdt,
+}
+
+/// Controls which root table is used on ACPI 2.0+ platforms, which provide both a RSDT and a XSDT.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RootTablePolicy {
+    /// Always use the XSDT, failing if it can't be used.
+    UseXsdt,
+    /// Use the XSDT, but fall back to the RSDT if the XSDT is invalid, or contains addresses that can't be
+    /// accessed on this platform. This works around firmware bugs.
+    FallBackToRsdt,
+    /// Always use the RSDT, ignoring the XSDT.
+    UseRsdt,
+}
+
 impl<H> AcpiTables<H>
 where
     H: AcpiHandler,
@@ -119,28 +206,103 @@ where
         Self::from_validated_rsdp(handler, rsdp_mapping)
     }
 
+    /// Search for the RSDP in the UEFI Configuration Table. See
+    /// [Rsdp::search_for_on_uefi](rsdp::Rsdp::search_for_on_uefi) for details.
+    ///
+    /// ### Safety
+    /// `config_table` must be the Configuration Table provided by the firmware (the `ConfigurationTable` and
+    /// `NumberOfTableEntries` fields of the `EFI_SYSTEM_TABLE`), so that the addresses of the ACPI entries point
+    /// to valid RSDPs.
+    pub unsafe fn search_for_rsdp_uefi(
+        handler: H,
+        config_table: &[ConfigurationTableEntry],
+    ) -> Result<AcpiTables<H>, AcpiError> {
+        let rsdp_mapping =
+            unsafe { Rsdp::search_for_on_uefi(handler.clone(), config_table) }.map_err(AcpiError::Rsdp)?;
+        Self::from_validated_rsdp(handler, rsdp_mapping)
+    }
+
+    /// Find the RSDP in the Multiboot2 boot information structure. See
+    /// [Rsdp::search_for_on_multiboot2](rsdp::Rsdp::search_for_on_multiboot2) for details.
+    ///
+    /// ### Safety
+    /// `info_address` must be the physical address of the boot information structure passed by a Multiboot2
+    /// bootloader, which must not have been overwritten since.
+    pub unsafe fn search_for_rsdp_multiboot2(handler: H, info_address: usize) -> Result<AcpiTables<H>, AcpiError> {
+        let rsdp_mapping =
+            unsafe { Rsdp::search_for_on_multiboot2(handler.clone(), info_address) }.map_err(AcpiError::Rsdp)?;
+        Self::from_validated_rsdp(handler, rsdp_mapping)
+    }
+
+    /// Find the RSDP using the Linux boot protocol's `boot_params` structure. See
+    /// [Rsdp::search_for_on_linux_boot_params](rsdp::Rsdp::search_for_on_linux_boot_params) for details.
+    ///
+    /// ### Safety
+    /// `boot_params_address` must be the physical address of the page containing the `boot_params` structure
+    /// passed to the kernel by the bootloader, which must not have been overwritten since.
+    pub unsafe fn search_for_rsdp_linux_boot_params(
+        handler: H,
+        boot_params_address: usize,
+    ) -> Result<AcpiTables<H>, AcpiError> {
+        let rsdp_mapping = unsafe { Rsdp::search_for_on_linux_boot_params(handler.clone(), boot_params_address) }
+            .map_err(AcpiError::Rsdp)?;
+        Self::from_validated_rsdp(handler, rsdp_mapping)
+    }
+
     /// Create an `AcpiTables` if you have a `PhysicalMapping` of the RSDP that you know is correct. This is called
     /// from `from_rsdp` after validation, but can also be used if you've searched for the RSDP manually on a BIOS
-    /// system.
+    /// system. On ACPI 2.0+ platforms, the XSDT is always used - see
+    /// [`AcpiTables::from_validated_rsdp_with_policy`] to fall back to the RSDT if the XSDT is unusable.
     pub fn from_validated_rsdp(
         handler: H,
         rsdp_mapping: PhysicalMapping<H, Rsdp>,
+    ) -> Result<AcpiTables<H>, AcpiError> {
+        Self::from_validated_rsdp_with_policy(handler, rsdp_mapping, RootTablePolicy::UseXsdt)
+    }
+
+    /// Create an `AcpiTables` from a validated RSDP, choosing between the RSDT and XSDT according to `policy`.
+    /// The root table that was used can be found with [`AcpiTables::root_table`].
+    ///
+    /// The XSDT is considered unusable if its address is zero, if its signature, length, or checksum is invalid,
+    /// or if its address or any of the addresses it contains can't be represented by a `usize` on this platform
+    /// (which would otherwise be silently truncated).
+    pub fn from_validated_rsdp_with_policy(
+        handler: H,
+        rsdp_mapping: PhysicalMapping<H, Rsdp>,
+        policy: RootTablePolicy,
     ) -> Result<AcpiTables<H>, AcpiError> {
         let revision = rsdp_mapping.revision();
+        let rsdt_address = rsdp_mapping.rsdt_address() as usize;
 
-        if revision == 0 {
-            /*
-             * We're running on ACPI Version 1.0. We should use the 32-bit RSDT address.
-             */
-            let rsdt_address = rsdp_mapping.rsdt_address();
-            unsafe { Self::from_rsdt(handler, revision, rsdt_address as usize) }
-        } else {
+        if revision == 0 || policy == RootTablePolicy::UseRsdt {
             /*
-             * We're running on ACPI Version 2.0+. We should use the 64-bit XSDT address, truncated
-             * to 32 bits on x86.
+             * We're running on ACPI Version 1.0, or have been told to ignore the XSDT. We should use the 32-bit
+             * RSDT address.
              */
-            let xsdt_address = rsdp_mapping.xsdt_address();
-            unsafe { Self::from_rsdt(handler, revision, xsdt_address as usize) }
+            return unsafe { Self::from_root_table(handler, revision, RootTable::Rsdt, rsdt_address) };
+        }
+
+        /*
+         * We're running on ACPI Version 2.0+. We should use the 64-bit XSDT address, as long as it (and the
+         * addresses inside it) are actually addressable on this platform. An address of zero means there is no
+         * XSDT, so we don't try to map it.
+         */
+        let xsdt_address = rsdp_mapping.xsdt_address();
+        let xsdt = usize::try_from(xsdt_address)
+            .map_err(|_| AcpiError::AddressTooLarge(xsdt_address))
+            .and_then(|xsdt_address| match xsdt_address {
+                0 => Err(AcpiError::TableMissing(Signature::XSDT)),
+                _ => Ok(xsdt_address),
+            })
+            .and_then(|xsdt_address| unsafe { Self::check_xsdt(&handler, xsdt_address) }.map(|_| xsdt_address));
+
+        match xsdt {
+            Ok(xsdt_address) => unsafe { Self::from_root_table(handler, revision, RootTable::Xsdt, xsdt_address) },
+            Err(err) if policy == RootTablePolicy::FallBackToRsdt && rsdt_address != 0 => {
+                warn!("XSDT is unusable ({:?}), falling back to the RSDT", err);
+                unsafe { Self::from_root_table(handler, revision, RootTable::Rsdt, rsdt_address) }
+            }
+            Err(err) => Err(err),
         }
     }
 
@@ -148,46 +310,87 @@ where
     /// bootloader reads the RSDP and passes you the address of the RSDT. You also need to supply the correct ACPI
     /// revision - if `0`, a RSDT is expected, while a `XSDT` is expected for greater revisions.
     pub unsafe fn from_rsdt(handler: H, revision: u8, rsdt_address: usize) -> Result<AcpiTables<H>, AcpiError> {
-        let mut result = AcpiTables { revision, sdts: BTreeMap::new(), dsdt: None, ssdts: Vec::new(), handler };
+        let root_table = if revision == 0 { RootTable::Rsdt } else { RootTable::Xsdt };
+        unsafe { Self::from_root_table(handler, revision, root_table, rsdt_address) }
+    }
 
-        let header = sdt::peek_at_sdt_header(&result.handler, rsdt_address);
-        let mapping =
-            unsafe { result.handler.map_physical_region::<SdtHeader>(rsdt_address, header.length as usize) };
+    unsafe fn from_root_table(
+        handler: H,
+        revision: u8,
+        root_table: RootTable,
+        address: usize,
+    ) -> Result<AcpiTables<H>, AcpiError> {
+        let mut result = AcpiTables {
+            revision,
+            sdts: BTreeMap::new(),
+            dsdt: None,
+            ssdts: Vec::new(),
+            all_tables: Vec::new(),
+            root_table: Some(root_table),
+            validation_policy: ValidationPolicy::Strict,
+            handler,
+        };
 
-        if revision == 0 {
-            /*
-             * ACPI Version 1.0. It's a RSDT!
-             */
-            mapping.validate(sdt::Signature::RSDT)?;
+        let header = sdt::peek_at_sdt_header(&result.handler, address);
+        let (signature, entry_size) = match root_table {
+            RootTable::Rsdt => (Signature::RSDT, mem::size_of::<u32>()),
+            RootTable::Xsdt => (Signature::XSDT, mem::size_of::<u64>()),
+        };
+        let num_tables = root_table_entries(signature, header.length, entry_size)?;
+        let mapping = unsafe { result.handler.map_physical_region::<SdtHeader>(address, header.length as usize) };
+        mapping.validate(signature)?;
+
+        match root_table {
+            RootTable::Rsdt => {
+                let tables_base =
+                    ((mapping.virtual_start.as_ptr() as usize) + mem::size_of::<SdtHeader>()) as *const u32;
+
+                for i in 0..num_tables {
+                    result.process_sdt(unsafe { tables_base.add(i).read_unaligned() as usize })?;
+                }
+            }
+            RootTable::Xsdt => {
+                let tables_base =
+                    ((mapping.virtual_start.as_ptr() as usize) + mem::size_of::<SdtHeader>()) as *const u64;
+
+                for i in 0..num_tables {
+                    let table_address = unsafe { tables_base.add(i).read_unaligned() };
+                    result.process_sdt(
+                        usize::try_from(table_address).map_err(|_| AcpiError::AddressTooLarge(table_address))?,
+                    )?;
+                }
+            }
+        }
 
-            let num_tables = (mapping.length as usize - mem::size_of::<SdtHeader>()) / mem::size_of::<u32>();
-            let tables_base =
-                ((mapping.virtual_start.as_ptr() as usize) + mem::size_of::<SdtHeader>()) as *const u32;
+        Ok(result)
+    }
 
-            for i in 0..num_tables {
-                result.process_sdt(unsafe { *tables_base.offset(i as isize) as usize })?;
-            }
-        } else {
-            /*
-             * ACPI Version 2.0+. It's a XSDT!
-             */
-            mapping.validate(sdt::Signature::XSDT)?;
+    /// Check that the XSDT at `xsdt_address` is valid, and that all of the addresses it contains can be used on
+    /// this platform.
+    unsafe fn check_xsdt(handler: &H, xsdt_address: usize) -> Result<(), AcpiError> {
+        let header = sdt::peek_at_sdt_header(handler, xsdt_address);
+        let num_tables = root_table_entries(Signature::XSDT, header.length, mem::size_of::<u64>())?;
+        let mapping = unsafe { handler.map_physical_region::<SdtHeader>(xsdt_address, header.length as usize) };
+        mapping.validate(Signature::XSDT)?;
 
-            let num_tables = (mapping.length as usize - mem::size_of::<SdtHeader>()) / mem::size_of::<u64>();
-            let tables_base =
-                ((mapping.virtual_start.as_ptr() as usize) + mem::size_of::<SdtHeader>()) as *const u64;
+        let tables_base = ((mapping.virtual_start.as_ptr() as usize) + mem::size_of::<SdtHeader>()) as *const u64;
 
-            for i in 0..num_tables {
-                result.process_sdt(unsafe { *tables_base.offset(i as isize) as usize })?;
+        for i in 0..num_tables {
+            let table_address = unsafe { tables_base.add(i).read_unaligned() };
+            if usize::try_from(table_address).is_err() {
+                return Err(AcpiError::AddressTooLarge(table_address));
             }
         }
 
-        Ok(result)
+        Ok(())
     }
 
     /// Construct an `AcpiTables` from a custom set of "discovered" tables. This is provided to allow the library
     /// to be used from unconventional settings (e.g. in userspace), for example with a `AcpiHandler` that detects
     /// accesses to specific physical addresses, and provides the correct data.
+    ///
+    /// As there is no RSDT/XSDT to take the order of the tables from, [`AcpiTables::all_tables`] will list the
+    /// tables in `sdts` in order of their signatures, followed by the SSDTs.
     pub fn from_tables_direct(
         handler: H,
         revision: u8,
@@ -195,12 +398,41 @@ where
         dsdt: Option<AmlTable>,
         ssdts: Vec<AmlTable>,
     ) -> AcpiTables<H> {
-        AcpiTables { revision, sdts, dsdt, ssdts, handler }
+        let all_tables = sdts
+            .values()
+            .map(|sdt| sdt.physical_address)
+            .chain(ssdts.iter().map(|ssdt| ssdt.address - mem::size_of::<SdtHeader>()))
+            .map(|physical_address| {
+                let header = sdt::peek_at_sdt_header(&handler, physical_address);
+                TableInfo { physical_address, length: header.length, header }
+            })
+            .collect();
+
+        AcpiTables {
+            revision,
+            sdts,
+            dsdt,
+            ssdts,
+            all_tables,
+            root_table: None,
+            validation_policy: ValidationPolicy::Strict,
+            handler,
+        }
     }
 
     fn process_sdt(&mut self, physical_address: usize) -> Result<(), AcpiError> {
         let header = sdt::peek_at_sdt_header(&self.handler, physical_address);
         trace!("Found ACPI table with signature {:?} and length {:?}", header.signature, header.length);
+        self.all_tables.push(TableInfo { physical_address, length: header.length, header });
+
+        /*
+         * Tables that are too short to contain a header can't be used. They're still recorded in `all_tables`, so
+         * they show up in the validation report.
+         */
+        if (header.length as usize) < mem::size_of::<SdtHeader>() {
+            warn!("Ignoring ACPI table with signature {:?}, which is too short", header.signature);
+            return Ok(());
+        }
 
         match header.signature {
             Signature::FADT => {
@@ -208,21 +440,37 @@ where
 
                 /*
                  * For whatever reason, they chose to put the DSDT inside the FADT, instead of just listing it
-                 * as another SDT. We extract it here to provide a nicer public API.
+                 * as another SDT. We extract it here to provide a nicer public API. If the FADT turns out to be
+                 * invalid, we still try to find the DSDT - whether the FADT can be used is decided by the
+                 * validation policy when it's accessed. A FADT that is too short for its revision can't be
+                 * trusted to point at the DSDT, and will be rejected when it's accessed.
                  */
-                let fadt_mapping =
-                    unsafe { self.handler.map_physical_region::<Fadt>(physical_address, mem::size_of::<Fadt>()) };
-                fadt_mapping.validate()?;
-
-                let dsdt_address = fadt_mapping.dsdt_address()?;
-                let dsdt_header = sdt::peek_at_sdt_header(&self.handler, dsdt_address);
-                self.dsdt = Some(AmlTable::new(dsdt_address, dsdt_header.length));
+                if (header.length as usize) < Fadt::min_length(header.revision) {
+                    warn!("FADT is too short for its revision, so the DSDT can't be found");
+                    self.sdts.insert(
+                        Signature::FADT,
+                        Sdt { physical_address, length: header.length, validated: false },
+                    );
+                    return Ok(());
+                }
 
-                /*
-                 * We've already validated the FADT to get the DSDT out, so it doesn't need to be done again.
-                 */
-                self.sdts
-                    .insert(Signature::FADT, Sdt { physical_address, length: header.length, validated: true });
+                let fadt_mapping =
+                    unsafe { self.handler.map_physical_region::<Fadt>(physical_address, header.length as usize) };
+                let validated = fadt_mapping.validate().is_ok();
+
+                match fadt_mapping.dsdt_address() {
+                    Ok(dsdt_address) => {
+                        let dsdt_header = sdt::peek_at_sdt_header(&self.handler, dsdt_address);
+                        if (dsdt_header.length as usize) >= mem::size_of::<SdtHeader>() {
+                            self.dsdt = Some(AmlTable::new(dsdt_address, dsdt_header.length));
+                        } else {
+                            warn!("Ignoring DSDT, which is too short");
+                        }
+                    }
+                    Err(err) => warn!("Couldn't find the DSDT: {:?}", err),
+                }
+
+                self.sdts.insert(Signature::FADT, Sdt { physical_address, length: header.length, validated });
             }
             Signature::SSDT => {
                 self.ssdts.push(AmlTable::new(physical_address, header.length));
@@ -257,12 +505,122 @@ where
         let mapping = unsafe { self.handler.map_physical_region::<T>(sdt.physical_address, sdt.length as usize) };
 
         if !sdt.validated {
-            mapping.header().validate(signature)?;
+            self.apply_validation_policy(mapping.header().validate(signature))?;
+        }
+
+        Ok(Some(mapping))
+    }
+
+    /// The root table that the other tables were found through. This is `None` if the tables were not found
+    /// through a root table (for example, if the `AcpiTables` was created with `from_tables_direct`).
+    pub fn root_table(&self) -> Option<RootTable> {
+        self.root_table
+    }
+
+    /// Get every table listed by the RSDT/XSDT, in the order they are listed. This includes tables that share a
+    /// signature with another table, of which only one is present in `sdts`, as well as the FADT and SSDTs.
+    pub fn all_tables(&self) -> &[TableInfo] {
+        &self.all_tables
+    }
+
+    /// Create a mapping to a table of type `T`. Unlike [`AcpiTables::get_sdt`], this is safe, as the table is
+    /// checked to be long enough for the parts of `T` that are used by a table of its revision. Tables that are
+    /// too short are rejected with `AcpiError::TableTooShort`, regardless of the validation policy.
+    pub fn get_table<T>(&self) -> Result<Option<PhysicalMapping<H, T>>, AcpiError>
+    where
+        T: CheckedTable,
+    {
+        let sdt = match self.sdts.get(&T::SIGNATURE) {
+            Some(sdt) => sdt,
+            None => return Ok(None),
+        };
+
+        let header = sdt::peek_at_sdt_header(&self.handler, sdt.physical_address);
+        if (header.length as usize) < T::min_length(header.revision) {
+            return Err(AcpiError::TableTooShort(T::SIGNATURE));
+        }
+
+        let mapping =
+            unsafe { self.handler.map_physical_region::<T>(sdt.physical_address, header.length as usize) };
+
+        if !sdt.validated {
+            self.apply_validation_policy(mapping.header().validate(T::SIGNATURE))?;
         }
 
         Ok(Some(mapping))
     }
 
+    /// Create a mapping to the `index`th table with the given signature, counting from zero in the order the
+    /// tables are listed by the RSDT/XSDT. This is useful for tables that a platform may provide more than one
+    /// of, which can't all be accessed through `get_sdt`. The table is validated every time it is mapped.
+    ///
+    /// ### Safety
+    /// The same requirements apply as for [`AcpiTables::get_sdt`].
+    pub unsafe fn get_sdt_instance<T>(
+        &self,
+        signature: sdt::Signature,
+        index: usize,
+    ) -> Result<Option<PhysicalMapping<H, T>>, AcpiError>
+    where
+        T: AcpiTable,
+    {
+        let table = match self.all_tables.iter().filter(|table| table.header.signature == signature).nth(index) {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        let mapping =
+            unsafe { self.handler.map_physical_region::<T>(table.physical_address, table.length as usize) };
+        self.apply_validation_policy(mapping.header().validate(signature))?;
+
+        Ok(Some(mapping))
+    }
+
+    /// Create a mapping to the FACS, which is found through the FADT. Returns `None` if the FADT doesn't point
+    /// to a FACS, which is allowed on hardware-reduced platforms.
+    pub fn facs(&self) -> Result<Option<PhysicalMapping<H, Facs>>, AcpiError> {
+        let fadt = self.get_table::<fadt::Fadt>()?.ok_or(AcpiError::TableMissing(Signature::FADT))?;
+        let facs_address = match fadt.facs_address() {
+            Some(address) => address,
+            None => return Ok(None),
+        };
+
+        let facs = unsafe { self.handler.map_physical_region::<Facs>(facs_address, mem::size_of::<Facs>()) };
+        self.apply_validation_policy(facs.validate())?;
+
+        Ok(Some(facs))
+    }
+
+    pub fn validation_policy(&self) -> ValidationPolicy {
+        self.validation_policy
+    }
+
+    /// Set the policy used when a table fails validation as it is accessed. By default, this is
+    /// `ValidationPolicy::Strict`. Problems with tables are not treated as errors while the `AcpiTables` is being
+    /// constructed, so this can be set before any table is accessed. The exception is the RSDT/XSDT, which must be
+    /// valid for the other tables to be found, and is always validated strictly during construction - see
+    /// [`RootTablePolicy::FallBackToRsdt`] to recover from a broken XSDT.
+    pub fn set_validation_policy(&mut self, policy: ValidationPolicy) {
+        self.validation_policy = policy;
+    }
+
+    /// Check all of the tables for problems, regardless of the validation policy. This is useful for logging
+    /// firmware bugs.
+    pub fn validation_report(&self) -> ValidationReport {
+        ValidationReport::new(self)
+    }
+
+    fn apply_validation_policy(&self, result: Result<(), AcpiError>) -> Result<(), AcpiError> {
+        match (result, self.validation_policy) {
+            (Ok(()), _) => Ok(()),
+            (Err(err), ValidationPolicy::Strict) => Err(err),
+            (Err(err), ValidationPolicy::Warn) => {
+                warn!("Using invalid ACPI table: {:?}", err);
+                Ok(())
+            }
+            (Err(_), ValidationPolicy::Ignore) => Ok(()),
+        }
+    }
+
     /// Convenience method for contructing a [`PlatformInfo`](crate::platform::PlatformInfo). This is one of the
     /// first things you should usually do with an `AcpiTables`, and allows to collect helpful information about
     /// the platform from the ACPI tables.
@@ -280,11 +638,50 @@ pub struct Sdt {
     pub validated: bool,
 }
 
+/// Describes one of the tables listed by the RSDT/XSDT.
+#[derive(Clone, Copy)]
+pub struct TableInfo {
+    /// Physical address of the start of the table, including the header.
+    pub physical_address: usize,
+    /// Length of the table in bytes.
+    pub length: u32,
+    pub header: SdtHeader,
+}
+
+/// Find the number of entries in a RSDT or XSDT of the given length, where each entry is `entry_size` bytes. The
+/// length is checked before the table is validated, as a length shorter than the header would otherwise underflow.
+fn root_table_entries(signature: Signature, length: u32, entry_size: usize) -> Result<usize, AcpiError> {
+    let entries_length = (length as usize)
+        .checked_sub(mem::size_of::<SdtHeader>())
+        .ok_or(AcpiError::SdtInvalidLength(signature))?;
+    if entries_length % entry_size != 0 {
+        return Err(AcpiError::SdtInvalidLength(signature));
+    }
+
+    Ok(entries_length / entry_size)
+}
+
 /// All types representing ACPI tables should implement this trait.
 pub trait AcpiTable {
     fn header(&self) -> &sdt::SdtHeader;
 }
 
+/// Table types that can be accessed safely with [`AcpiTables::get_table`], because the length of a table can be
+/// checked against the parts of the type that will be accessed.
+///
+/// ### Safety
+/// The type must correctly represent the layout of the table with the signature `SIGNATURE`, and `min_length`
+/// must return a length that covers every field that is accessed on a table of the given revision.
+pub unsafe trait CheckedTable: AcpiTable + Sized {
+    const SIGNATURE: sdt::Signature;
+
+    /// The minimum length of a table of the given revision. By default, this is the size of the type, but types
+    /// that use [`ExtendedField`](crate::sdt::ExtendedField)s should take the revision into account.
+    fn min_length(_revision: u8) -> usize {
+        mem::size_of::<Self>()
+    }
+}
+
 #[derive(Debug)]
 pub struct AmlTable {
     /// Physical address of the start of the AML stream (excluding the table header).
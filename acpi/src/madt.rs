@@ -1,26 +1,45 @@
 use crate::{
     platform::{
         Apic,
+        Gic,
+        GicCpuInterface,
+        GicDistributor,
+        GicIts,
+        GicMsiFrame,
+        GicRedistributor,
+        GicVersion,
         InterruptModel,
         InterruptSourceOverride,
         IoApic,
+        IoSapic,
         LocalInterruptLine,
+        LocalSapic,
+        MultiprocessorWakeup,
         NmiLine,
         NmiProcessor,
         NmiSource,
+        PlatformInterruptSource,
+        PlatformInterruptType,
         Polarity,
         Processor,
         ProcessorInfo,
         ProcessorState,
+        RiscV,
+        RiscVAplic,
+        RiscVImsic,
+        RiscVPlic,
+        RiscVRintc,
+        Sapic,
         TriggerMode,
     },
-    sdt::SdtHeader,
+    sdt::{self, SdtHeader, Signature, SubtableFormat, SubtableIter},
     AcpiError,
     AcpiTable,
+    CheckedTable,
 };
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 use bit_field::BitField;
-use core::{marker::PhantomData, mem};
+use core::{mem, slice, str};
 
 #[derive(Debug)]
 pub enum MadtError {
@@ -29,6 +48,7 @@ pub enum MadtError {
     InvalidLocalNmiLine,
     MpsIntiInvalidPolarity,
     MpsIntiInvalidTriggerMode,
+    InvalidLocalSapicUidString,
 }
 
 /// Represents the MADT - this contains the MADT header fields. You can then iterate over a `Madt`
@@ -52,40 +72,60 @@ impl AcpiTable for Madt {
     }
 }
 
+unsafe impl CheckedTable for Madt {
+    const SIGNATURE: Signature = Signature::MADT;
+}
+
 impl Madt {
     pub fn parse_interrupt_model(&self) -> Result<(InterruptModel, Option<ProcessorInfo>), AcpiError> {
         /*
-         * We first do a pass through the MADT to determine which interrupt model is being used.
+         * We first do a pass through the MADT to determine which interrupt model is being used. The SAPIC model
+         * shares some entries with the APIC model, and firmware can list both, so it's chosen if any
+         * SAPIC-specific entries are present.
          */
+        if self.entries().any(|entry| {
+            matches!(
+                entry,
+                MadtEntry::IoSapic(_) | MadtEntry::LocalSapic(_) | MadtEntry::PlatformInterruptSource(_)
+            )
+        }) {
+            return self.parse_sapic_model();
+        }
+
         for entry in self.entries() {
             match entry {
             MadtEntry::LocalApic(_) |
+            MadtEntry::LocalX2Apic(_) |
             MadtEntry::IoApic(_) |
             MadtEntry::InterruptSourceOverride(_) |
             MadtEntry::NmiSource(_) |   // TODO: is this one used by more than one model?
             MadtEntry::LocalApicNmi(_) |
+            MadtEntry::X2ApicNmi(_) |
             MadtEntry::LocalApicAddressOverride(_) => {
                 return self.parse_apic_model();
             }
 
-            MadtEntry::IoSapic(_) |
-            MadtEntry::LocalSapic(_) |
-            MadtEntry::PlatformInterruptSource(_) => {
-                unimplemented!();
-            }
-
-            MadtEntry::LocalX2Apic(_) |
-            MadtEntry::X2ApicNmi(_) => {
-                unimplemented!();
-            }
-
-            MadtEntry::Gicc(_) |
+            MadtEntry::Gicc(..) |
             MadtEntry::Gicd(_) |
             MadtEntry::GicMsiFrame(_) |
             MadtEntry::GicRedistributor(_) |
             MadtEntry::GicInterruptTranslationService(_) => {
-                unimplemented!();
+                return self.parse_gic_model();
+            }
+
+            MadtEntry::RiscVRintc(_) |
+            MadtEntry::RiscVImsic(_) |
+            MadtEntry::RiscVAplic(_) |
+            MadtEntry::RiscVPlic(_) => {
+                return self.parse_riscv_model();
             }
+
+            // SAPIC entries were handled by the scan above
+            MadtEntry::IoSapic(_) |
+            MadtEntry::LocalSapic(_) |
+            MadtEntry::PlatformInterruptSource(_) |
+            MadtEntry::MultiprocessorWakeup(_) |
+            MadtEntry::Unknown { .. } => ()
         }
         }
 
@@ -106,8 +146,8 @@ impl Madt {
                 MadtEntry::IoApic(_) => io_apic_count += 1,
                 MadtEntry::InterruptSourceOverride(_) => iso_count += 1,
                 MadtEntry::NmiSource(_) => nmi_source_count += 1,
-                MadtEntry::LocalApicNmi(_) => local_nmi_line_count += 1,
-                MadtEntry::LocalApic(_) => processor_count += 1,
+                MadtEntry::LocalApicNmi(_) | MadtEntry::X2ApicNmi(_) => local_nmi_line_count += 1,
+                MadtEntry::LocalApic(_) | MadtEntry::LocalX2Apic(_) => processor_count += 1,
                 _ => (),
             }
         }
@@ -136,8 +176,37 @@ impl Madt {
                     };
 
                     let processor = Processor {
-                        processor_uid: entry.processor_id,
-                        local_apic_id: entry.apic_id,
+                        processor_uid: entry.processor_id as u32,
+                        local_apic_id: entry.apic_id as u32,
+                        state,
+                        is_ap,
+                    };
+
+                    if is_ap {
+                        application_processors.push(processor);
+                    } else {
+                        boot_processor = Some(processor);
+                    }
+                }
+
+                MadtEntry::LocalX2Apic(ref entry) => {
+                    /*
+                     * x2APIC entries describe processors in the same way as local APIC entries, but with 32-bit
+                     * APIC IDs and UIDs. Firmware can mix the two, so these are added to the same list in the
+                     * order they appear in the MADT.
+                     */
+                    let is_ap = boot_processor.is_some();
+                    let is_disabled = !{ entry.flags }.get_bit(0);
+
+                    let state = match (is_ap, is_disabled) {
+                        (_, true) => ProcessorState::Disabled,
+                        (true, false) => ProcessorState::WaitingForSipi,
+                        (false, false) => ProcessorState::Running,
+                    };
+
+                    let processor = Processor {
+                        processor_uid: entry.processor_uid,
+                        local_apic_id: entry.x2apic_id,
                         state,
                         is_ap,
                     };
@@ -195,10 +264,25 @@ impl Madt {
                     },
                 }),
 
+                MadtEntry::X2ApicNmi(ref entry) => local_apic_nmi_lines.push(NmiLine {
+                    processor: if entry.processor_uid == 0xffffffff {
+                        NmiProcessor::All
+                    } else {
+                        NmiProcessor::ProcessorUid(entry.processor_uid)
+                    },
+                    line: match entry.nmi_line {
+                        0 => LocalInterruptLine::Lint0,
+                        1 => LocalInterruptLine::Lint1,
+                        _ => return Err(AcpiError::InvalidMadt(MadtError::InvalidLocalNmiLine)),
+                    },
+                }),
+
                 MadtEntry::LocalApicAddressOverride(ref entry) => {
                     local_apic_address = entry.local_apic_address;
                 }
 
+                MadtEntry::MultiprocessorWakeup(_) | MadtEntry::Unknown { .. } => (),
+
                 _ => {
                     return Err(AcpiError::InvalidMadt(MadtError::UnexpectedEntry));
                 }
@@ -214,16 +298,423 @@ impl Madt {
                 nmi_sources,
                 also_has_legacy_pics: self.supports_8259(),
             }),
-            Some(ProcessorInfo { boot_processor: boot_processor.unwrap(), application_processors }),
+            boot_processor.map(|boot_processor| ProcessorInfo {
+                boot_processor,
+                application_processors,
+                multiprocessor_wakeup: self.multiprocessor_wakeup(),
+            }),
+        ))
+    }
+
+    fn parse_sapic_model(&self) -> Result<(InterruptModel, Option<ProcessorInfo>), AcpiError> {
+        let mut local_sapic_address = self.local_apic_address as u64;
+        let mut io_sapic_count = 0;
+        let mut local_sapic_count = 0;
+        let mut platform_interrupt_source_count = 0;
+        let mut iso_count = 0;
+        let mut nmi_source_count = 0;
+        let mut local_nmi_line_count = 0;
+
+        // Do a pass over the entries so we know how much space we should reserve in the vectors
+        for entry in self.entries() {
+            match entry {
+                MadtEntry::IoSapic(_) => io_sapic_count += 1,
+                MadtEntry::LocalSapic(_) => local_sapic_count += 1,
+                MadtEntry::PlatformInterruptSource(_) => platform_interrupt_source_count += 1,
+                MadtEntry::InterruptSourceOverride(_) => iso_count += 1,
+                MadtEntry::NmiSource(_) => nmi_source_count += 1,
+                MadtEntry::LocalApicNmi(_) => local_nmi_line_count += 1,
+                _ => (),
+            }
+        }
+
+        let mut io_sapics = Vec::with_capacity(io_sapic_count);
+        let mut local_sapics = Vec::with_capacity(local_sapic_count);
+        let mut platform_interrupt_sources = Vec::with_capacity(platform_interrupt_source_count);
+        let mut interrupt_source_overrides = Vec::with_capacity(iso_count);
+        let mut nmi_sources = Vec::with_capacity(nmi_source_count);
+        let mut local_sapic_nmi_lines = Vec::with_capacity(local_nmi_line_count);
+        let mut boot_processor = None;
+        let mut application_processors = Vec::with_capacity(local_sapic_count.saturating_sub(1)); // Subtract one for the BSP
+
+        for entry in self.entries() {
+            match entry {
+                MadtEntry::LocalSapic(ref entry) => {
+                    let enabled = { entry.flags }.get_bit(0);
+
+                    /*
+                     * As with the APIC model, the first processor is the BSP, and subsequent ones are APs. The Local
+                     * SAPIC entries don't say which processor booted the system, so this relies on firmware listing
+                     * it first.
+                     */
+                    let is_ap = boot_processor.is_some();
+                    let state = match (is_ap, enabled) {
+                        (_, false) => ProcessorState::Disabled,
+                        (true, true) => ProcessorState::WaitingForSipi,
+                        (false, true) => ProcessorState::Running,
+                    };
+
+                    let processor = Processor {
+                        processor_uid: entry.processor_uid,
+                        local_apic_id: ((entry.local_sapic_id as u32) << 8) | entry.local_sapic_eid as u32,
+                        state,
+                        is_ap,
+                    };
+
+                    if is_ap {
+                        application_processors.push(processor);
+                    } else {
+                        boot_processor = Some(processor);
+                    }
+
+                    local_sapics.push(LocalSapic {
+                        processor_id: entry.processor_id,
+                        local_sapic_id: entry.local_sapic_id,
+                        local_sapic_eid: entry.local_sapic_eid,
+                        enabled,
+                        processor_uid: entry.processor_uid,
+                        processor_uid_string: entry.processor_uid_string()?,
+                    });
+                }
+
+                MadtEntry::IoSapic(ref entry) => {
+                    io_sapics.push(IoSapic {
+                        id: entry.io_apic_id,
+                        address: entry.io_sapic_address,
+                        global_system_interrupt_base: entry.global_system_interrupt_base,
+                    });
+                }
+
+                MadtEntry::PlatformInterruptSource(ref entry) => {
+                    let (polarity, trigger_mode) = parse_mps_inti_flags(entry.flags)?;
+
+                    platform_interrupt_sources.push(PlatformInterruptSource {
+                        interrupt_type: match entry.interrupt_type {
+                            1 => PlatformInterruptType::Pmi,
+                            2 => PlatformInterruptType::Init,
+                            3 => PlatformInterruptType::CorrectedPlatformError,
+                            other => PlatformInterruptType::Reserved(other),
+                        },
+                        polarity,
+                        trigger_mode,
+                        processor_id: entry.processor_id,
+                        processor_eid: entry.processor_eid,
+                        io_sapic_vector: entry.io_sapic_vector,
+                        global_system_interrupt: entry.global_system_interrupt,
+                        cpei_processor_override: { entry.platform_interrupt_source_flags }.get_bit(0),
+                    });
+                }
+
+                MadtEntry::InterruptSourceOverride(ref entry) => {
+                    if entry.bus != 0 {
+                        return Err(AcpiError::InvalidMadt(MadtError::InterruptOverrideEntryHasInvalidBus));
+                    }
+
+                    let (polarity, trigger_mode) = parse_mps_inti_flags(entry.flags)?;
+
+                    interrupt_source_overrides.push(InterruptSourceOverride {
+                        isa_source: entry.irq,
+                        global_system_interrupt: entry.global_system_interrupt,
+                        polarity,
+                        trigger_mode,
+                    });
+                }
+
+                MadtEntry::NmiSource(ref entry) => {
+                    let (polarity, trigger_mode) = parse_mps_inti_flags(entry.flags)?;
+
+                    nmi_sources.push(NmiSource {
+                        global_system_interrupt: entry.global_system_interrupt,
+                        polarity,
+                        trigger_mode,
+                    });
+                }
+
+                MadtEntry::LocalApicNmi(ref entry) => local_sapic_nmi_lines.push(NmiLine {
+                    processor: if entry.processor_id == 0xff {
+                        NmiProcessor::All
+                    } else {
+                        NmiProcessor::ProcessorUid(entry.processor_id as u32)
+                    },
+                    line: match entry.nmi_line {
+                        0 => LocalInterruptLine::Lint0,
+                        1 => LocalInterruptLine::Lint1,
+                        _ => return Err(AcpiError::InvalidMadt(MadtError::InvalidLocalNmiLine)),
+                    },
+                }),
+
+                MadtEntry::LocalApicAddressOverride(ref entry) => {
+                    local_sapic_address = entry.local_apic_address;
+                }
+
+                /*
+                 * I/O SAPICs must be used in place of any I/O APICs, and local SAPICs in place of local APICs,
+                 * so we ignore any APIC entries the firmware lists alongside them.
+                 */
+                MadtEntry::LocalApic(_)
+                | MadtEntry::IoApic(_)
+                | MadtEntry::LocalX2Apic(_)
+                | MadtEntry::X2ApicNmi(_)
+                | MadtEntry::MultiprocessorWakeup(_)
+                | MadtEntry::Unknown { .. } => (),
+
+                _ => {
+                    return Err(AcpiError::InvalidMadt(MadtError::UnexpectedEntry));
+                }
+            }
+        }
+
+        Ok((
+            InterruptModel::Sapic(Sapic {
+                local_sapic_address,
+                io_sapics,
+                local_sapics,
+                platform_interrupt_sources,
+                local_sapic_nmi_lines,
+                interrupt_source_overrides,
+                nmi_sources,
+            }),
+            boot_processor.map(|boot_processor| ProcessorInfo {
+                boot_processor,
+                application_processors,
+                multiprocessor_wakeup: self.multiprocessor_wakeup(),
+            }),
+        ))
+    }
+
+    fn parse_gic_model(&self) -> Result<(InterruptModel, Option<ProcessorInfo>), AcpiError> {
+        let mut distributor = None;
+        let mut cpu_interface_count = 0;
+        let mut redistributor_count = 0;
+        let mut its_count = 0;
+        let mut msi_frame_count = 0;
+
+        // Do a pass over the entries so we know how much space we should reserve in the vectors
+        for entry in self.entries() {
+            match entry {
+                MadtEntry::Gicc(..) => cpu_interface_count += 1,
+                MadtEntry::GicRedistributor(_) => redistributor_count += 1,
+                MadtEntry::GicInterruptTranslationService(_) => its_count += 1,
+                MadtEntry::GicMsiFrame(_) => msi_frame_count += 1,
+                _ => (),
+            }
+        }
+
+        let mut cpu_interfaces = Vec::with_capacity(cpu_interface_count);
+        let mut redistributors = Vec::with_capacity(redistributor_count);
+        let mut interrupt_translation_services = Vec::with_capacity(its_count);
+        let mut msi_frames = Vec::with_capacity(msi_frame_count);
+        let mut boot_processor = None;
+        let mut application_processors = Vec::with_capacity(cpu_interface_count.saturating_sub(1)); // Subtract one for the BSP
+
+        for entry in self.entries() {
+            match entry {
+                MadtEntry::Gicc(ref entry, processor_power_efficiency_class) => {
+                    let flags = entry.flags;
+                    let enabled = flags.get_bit(0);
+                    let trigger_mode = |edge| if edge { TriggerMode::Edge } else { TriggerMode::Level };
+
+                    /*
+                     * As with the APIC model, the first processor is the BSP, and subsequent ones are APs.
+                     */
+                    let is_ap = boot_processor.is_some();
+                    let state = match (is_ap, enabled) {
+                        (_, false) => ProcessorState::Disabled,
+                        (true, true) => ProcessorState::WaitingForSipi,
+                        (false, true) => ProcessorState::Running,
+                    };
+
+                    let processor = Processor {
+                        processor_uid: entry.processor_uid,
+                        local_apic_id: mpidr_to_id(entry.mpidr),
+                        state,
+                        is_ap,
+                    };
+
+                    if is_ap {
+                        application_processors.push(processor);
+                    } else {
+                        boot_processor = Some(processor);
+                    }
+
+                    cpu_interfaces.push(GicCpuInterface {
+                        cpu_interface_number: entry.cpu_interface_number,
+                        processor_uid: entry.processor_uid,
+                        enabled,
+                        mpidr: entry.mpidr,
+                        parking_protocol_version: entry.parking_protocol_version,
+                        parked_address: entry.parked_address,
+                        performance_interrupt_gsiv: entry.performance_interrupt_gsiv,
+                        performance_interrupt_trigger_mode: trigger_mode(flags.get_bit(1)),
+                        vgic_maintenance_interrupt: entry.vgic_maintenance_interrupt,
+                        vgic_maintenance_interrupt_trigger_mode: trigger_mode(flags.get_bit(2)),
+                        physical_base_address: entry.physical_base_address,
+                        gicv_base_address: entry.gicv_base_address,
+                        gich_base_address: entry.gich_base_address,
+                        gicr_base_address: entry.gicr_base_address,
+                        processor_power_efficiency_class,
+                    });
+                }
+
+                MadtEntry::Gicd(ref entry) => {
+                    distributor = Some(GicDistributor {
+                        id: entry.gic_id,
+                        physical_base_address: entry.physical_base_address,
+                        version: match entry.gic_version {
+                            0x00 => GicVersion::HardwareDiscovery,
+                            0x01 => GicVersion::V1,
+                            0x02 => GicVersion::V2,
+                            0x03 => GicVersion::V3,
+                            0x04 => GicVersion::V4,
+                            other => GicVersion::Reserved(other),
+                        },
+                    });
+                }
+
+                MadtEntry::GicRedistributor(ref entry) => {
+                    redistributors.push(GicRedistributor {
+                        discovery_range_base_address: entry.discovery_range_base_address,
+                        discovery_range_length: entry.discovery_range_length,
+                    });
+                }
+
+                MadtEntry::GicInterruptTranslationService(ref entry) => {
+                    interrupt_translation_services
+                        .push(GicIts { id: entry.id, physical_base_address: entry.physical_base_address });
+                }
+
+                MadtEntry::GicMsiFrame(ref entry) => {
+                    msi_frames.push(GicMsiFrame {
+                        id: entry.frame_id,
+                        physical_base_address: entry.physical_base_address,
+                        spi_count_and_base_valid: { entry.flags }.get_bit(0),
+                        spi_count: entry.spi_count,
+                        spi_base: entry.spi_base,
+                    });
+                }
+
+                MadtEntry::MultiprocessorWakeup(_) | MadtEntry::Unknown { .. } => (),
+
+                _ => {
+                    return Err(AcpiError::InvalidMadt(MadtError::UnexpectedEntry));
+                }
+            }
+        }
+
+        Ok((
+            InterruptModel::Gic(Gic {
+                distributor,
+                cpu_interfaces,
+                redistributors,
+                interrupt_translation_services,
+                msi_frames,
+            }),
+            boot_processor.map(|boot_processor| ProcessorInfo {
+                boot_processor,
+                application_processors,
+                multiprocessor_wakeup: self.multiprocessor_wakeup(),
+            }),
         ))
     }
 
-    pub fn entries(&self) -> MadtEntryIter {
-        MadtEntryIter {
-            pointer: unsafe { (self as *const Madt as *const u8).offset(mem::size_of::<Madt>() as isize) },
-            remaining_length: self.header.length - mem::size_of::<Madt>() as u32,
-            _phantom: PhantomData,
+    fn parse_riscv_model(&self) -> Result<(InterruptModel, Option<ProcessorInfo>), AcpiError> {
+        let mut imsic = None;
+        let mut hart_count = 0;
+        let mut aplic_count = 0;
+        let mut plic_count = 0;
+
+        // Do a pass over the entries so we know how much space we should reserve in the vectors
+        for entry in self.entries() {
+            match entry {
+                MadtEntry::RiscVRintc(_) => hart_count += 1,
+                MadtEntry::RiscVAplic(_) => aplic_count += 1,
+                MadtEntry::RiscVPlic(_) => plic_count += 1,
+                _ => (),
+            }
         }
+
+        let mut harts = Vec::with_capacity(hart_count);
+        let mut aplics = Vec::with_capacity(aplic_count);
+        let mut plics = Vec::with_capacity(plic_count);
+
+        for entry in self.entries() {
+            match entry {
+                MadtEntry::RiscVRintc(ref entry) => {
+                    harts.push(RiscVRintc {
+                        hart_id: entry.hart_id,
+                        processor_uid: entry.processor_uid,
+                        enabled: { entry.flags }.get_bit(0),
+                        external_interrupt_controller_id: entry.external_interrupt_controller_id,
+                        imsic_address: entry.imsic_address,
+                        imsic_size: entry.imsic_size,
+                    });
+                }
+
+                MadtEntry::RiscVImsic(ref entry) => {
+                    imsic = Some(RiscVImsic {
+                        num_ids: entry.num_ids,
+                        num_guest_ids: entry.num_guest_ids,
+                        guest_index_bits: entry.guest_index_bits,
+                        hart_index_bits: entry.hart_index_bits,
+                        group_index_bits: entry.group_index_bits,
+                        group_index_shift: entry.group_index_shift,
+                    });
+                }
+
+                MadtEntry::RiscVAplic(ref entry) => {
+                    aplics.push(RiscVAplic {
+                        id: entry.aplic_id,
+                        hardware_id: entry.hardware_id,
+                        num_idcs: entry.num_idcs,
+                        num_sources: entry.num_sources,
+                        global_system_interrupt_base: entry.global_system_interrupt_base,
+                        address: entry.aplic_address,
+                        size: entry.aplic_size,
+                    });
+                }
+
+                MadtEntry::RiscVPlic(ref entry) => {
+                    plics.push(RiscVPlic {
+                        id: entry.plic_id,
+                        hardware_id: entry.hardware_id,
+                        num_sources: entry.num_sources,
+                        max_priority: entry.max_priority,
+                        global_system_interrupt_base: entry.global_system_interrupt_base,
+                        address: entry.plic_address,
+                        size: entry.plic_size,
+                    });
+                }
+
+                MadtEntry::MultiprocessorWakeup(_) | MadtEntry::Unknown { .. } => (),
+
+                _ => {
+                    return Err(AcpiError::InvalidMadt(MadtError::UnexpectedEntry));
+                }
+            }
+        }
+
+        /*
+         * The boot hart is not necessarily the first one listed in the MADT (it's instead passed to the OS by the
+         * firmware), so we don't produce a `ProcessorInfo` for RISC-V systems.
+         */
+        Ok((InterruptModel::RiscV(RiscV { harts, imsic, aplics, plics }), None))
+    }
+
+    /// Find the Multiprocessor Wakeup structure, if present. Platforms that provide this (such as confidential
+    /// computing guests) expect application processors to be started using the mailbox it describes, rather than
+    /// with INIT-SIPI-SIPI.
+    pub fn multiprocessor_wakeup(&self) -> Option<MultiprocessorWakeup> {
+        self.entries().find_map(|entry| match entry {
+            MadtEntry::MultiprocessorWakeup(entry) => Some(MultiprocessorWakeup {
+                mailbox_version: entry.mailbox_version,
+                mailbox_address: entry.mailbox_address,
+            }),
+            _ => None,
+        })
+    }
+
+    pub fn entries(&self) -> MadtEntryIter<'_> {
+        MadtEntryIter { subtables: SubtableIter::new(unsafe { sdt::table_contents(self) }, SubtableFormat::Short) }
     }
 
     pub fn supports_8259(&self) -> bool {
@@ -231,14 +722,14 @@ impl Madt {
     }
 }
 
+/// Pack the affinity fields of an MPIDR into 32 bits, with `Aff3` in the top byte. This uniquely identifies a
+/// processor, unlike the GIC CPU interface number, which is `0` for every processor from GICv3 onwards.
+fn mpidr_to_id(mpidr: u64) -> u32 {
+    ((mpidr.get_bits(32..40) as u32) << 24) | mpidr.get_bits(0..24) as u32
+}
+
 pub struct MadtEntryIter<'a> {
-    pointer: *const u8,
-    /*
-     * The iterator can only have at most `u32::MAX` remaining bytes, because the length of the
-     * whole SDT can only be at most `u32::MAX`.
-     */
-    remaining_length: u32,
-    _phantom: PhantomData<&'a ()>,
+    subtables: SubtableIter<'a>,
 }
 
 pub enum MadtEntry<'a> {
@@ -253,77 +744,100 @@ pub enum MadtEntry<'a> {
     PlatformInterruptSource(&'a PlatformInterruptSourceEntry),
     LocalX2Apic(&'a LocalX2ApicEntry),
     X2ApicNmi(&'a X2ApicNmiEntry),
-    Gicc(&'a GiccEntry),
+    /// A GICC entry, and its processor power efficiency class if the entry is long enough to have one (the field
+    /// was added in ACPI 6.0).
+    Gicc(&'a GiccEntry, Option<u8>),
     Gicd(&'a GicdEntry),
     GicMsiFrame(&'a GicMsiFrameEntry),
     GicRedistributor(&'a GicRedistributorEntry),
     GicInterruptTranslationService(&'a GicInterruptTranslationServiceEntry),
+    MultiprocessorWakeup(&'a MultiprocessorWakeupEntry),
+    RiscVRintc(&'a RiscVRintcEntry),
+    RiscVImsic(&'a RiscVImsicEntry),
+    RiscVAplic(&'a RiscVAplicEntry),
+    RiscVPlic(&'a RiscVPlicEntry),
+    /// An entry of a type this library does not understand, such as an OEM-specific entry (types `0x80..=0xff`).
+    /// `bytes` contains the whole entry, including its type and length fields.
+    Unknown {
+        entry_type: u8,
+        bytes: &'a [u8],
+    },
 }
 
 impl<'a> Iterator for MadtEntryIter<'a> {
     type Item = MadtEntry<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while self.remaining_length > 0 {
-            let entry_pointer = self.pointer;
-            let header = unsafe { *(self.pointer as *const EntryHeader) };
-
-            self.pointer = unsafe { self.pointer.offset(header.length as isize) };
-            self.remaining_length -= header.length as u32;
-
-            macro_rules! construct_entry {
-                ($entry_type:expr,
-                 $entry_pointer:expr,
-                 $(($value:expr => $variant:path as $type:ty)),*
-                ) => {
-                    match $entry_type {
-                        $(
-                            $value => {
-                                return Some($variant(unsafe {
-                                    &*($entry_pointer as *const $type)
-                                }))
+        /*
+         * If an entry is malformed, we can't find the next one (and would loop forever on a zero-length entry), so
+         * stop iterating.
+         */
+        let subtable = self.subtables.next()?.ok()?;
+        let entry_type = subtable.subtable_type as u8;
+
+        /*
+         * GICC entries from ACPI 5.1 are four bytes shorter than later ones, as they don't have the processor
+         * power efficiency class, so they're accepted without it.
+         */
+        if entry_type == 0xb {
+            if let Some(entry) = unsafe { subtable.get::<GiccEntry>() } {
+                let processor_power_efficiency_class = subtable.bytes.get(mem::size_of::<GiccEntry>()).copied();
+                return Some(MadtEntry::Gicc(entry, processor_power_efficiency_class));
+            }
+        }
+
+        macro_rules! construct_entry {
+            ($entry_type:expr,
+             $subtable:expr,
+             $(($value:expr => $variant:path as $type:ty)),*
+            ) => {
+                match $entry_type {
+                    $(
+                        $value => {
+                            if let Some(entry) = unsafe { $subtable.get::<$type>() } {
+                                return Some($variant(entry));
                             }
-                         )*
-
-                        /*
-                         * These entry types are reserved by the ACPI standard. We should skip them
-                         * if they appear in a real MADT.
-                         */
-                        0x10..=0x7f => {}
-
-                        /*
-                         * These entry types are reserved for OEM use. Atm, we just skip them too.
-                         * TODO: work out if we should ever do anything else here
-                         */
-                        0x80..=0xff => {}
-                    }
+                        }
+                     )*
+
+                    _ => ()
                 }
             }
-
-            #[rustfmt::skip]
-            construct_entry!(
-                header.entry_type,
-                entry_pointer,
-                (0x0 => MadtEntry::LocalApic as LocalApicEntry),
-                (0x1 => MadtEntry::IoApic as IoApicEntry),
-                (0x2 => MadtEntry::InterruptSourceOverride as InterruptSourceOverrideEntry),
-                (0x3 => MadtEntry::NmiSource as NmiSourceEntry),
-                (0x4 => MadtEntry::LocalApicNmi as LocalApicNmiEntry),
-                (0x5 => MadtEntry::LocalApicAddressOverride as LocalApicAddressOverrideEntry),
-                (0x6 => MadtEntry::IoSapic as IoSapicEntry),
-                (0x7 => MadtEntry::LocalSapic as LocalSapicEntry),
-                (0x8 => MadtEntry::PlatformInterruptSource as PlatformInterruptSourceEntry),
-                (0x9 => MadtEntry::LocalX2Apic as LocalX2ApicEntry),
-                (0xa => MadtEntry::X2ApicNmi as X2ApicNmiEntry),
-                (0xb => MadtEntry::Gicc as GiccEntry),
-                (0xc => MadtEntry::Gicd as GicdEntry),
-                (0xd => MadtEntry::GicMsiFrame as GicMsiFrameEntry),
-                (0xe => MadtEntry::GicRedistributor as GicRedistributorEntry),
-                (0xf => MadtEntry::GicInterruptTranslationService as GicInterruptTranslationServiceEntry)
-            );
         }
 
-        None
+        #[rustfmt::skip]
+        construct_entry!(
+            entry_type,
+            subtable,
+            (0x0 => MadtEntry::LocalApic as LocalApicEntry),
+            (0x1 => MadtEntry::IoApic as IoApicEntry),
+            (0x2 => MadtEntry::InterruptSourceOverride as InterruptSourceOverrideEntry),
+            (0x3 => MadtEntry::NmiSource as NmiSourceEntry),
+            (0x4 => MadtEntry::LocalApicNmi as LocalApicNmiEntry),
+            (0x5 => MadtEntry::LocalApicAddressOverride as LocalApicAddressOverrideEntry),
+            (0x6 => MadtEntry::IoSapic as IoSapicEntry),
+            (0x7 => MadtEntry::LocalSapic as LocalSapicEntry),
+            (0x8 => MadtEntry::PlatformInterruptSource as PlatformInterruptSourceEntry),
+            (0x9 => MadtEntry::LocalX2Apic as LocalX2ApicEntry),
+            (0xa => MadtEntry::X2ApicNmi as X2ApicNmiEntry),
+            (0xc => MadtEntry::Gicd as GicdEntry),
+            (0xd => MadtEntry::GicMsiFrame as GicMsiFrameEntry),
+            (0xe => MadtEntry::GicRedistributor as GicRedistributorEntry),
+            (0xf => MadtEntry::GicInterruptTranslationService as GicInterruptTranslationServiceEntry),
+            (0x10 => MadtEntry::MultiprocessorWakeup as MultiprocessorWakeupEntry),
+            (0x18 => MadtEntry::RiscVRintc as RiscVRintcEntry),
+            (0x19 => MadtEntry::RiscVImsic as RiscVImsicEntry),
+            (0x1a => MadtEntry::RiscVAplic as RiscVAplicEntry),
+            (0x1b => MadtEntry::RiscVPlic as RiscVPlicEntry)
+        );
+
+        /*
+         * These entry types are either reserved by the ACPI standard, reserved for OEM use, or not yet supported
+         * by this library. They're returned as raw bytes so they can be parsed by the caller. Entries of a known
+         * type that are too short to be valid (such as those from an older revision of the structure) are also
+         * returned like this.
+         */
+        Some(MadtEntry::Unknown { entry_type, bytes: subtable.bytes })
     }
 }
 
@@ -410,6 +924,28 @@ pub struct LocalSapicEntry {
     processor_uid_string: u8,
 }
 
+impl LocalSapicEntry {
+    /// Read the processor's UID string, if present. This extends from the `processor_uid_string` field to the end
+    /// of the entry, and is terminated by a null byte.
+    fn processor_uid_string(&self) -> Result<Option<String>, AcpiError> {
+        let offset = mem::size_of::<LocalSapicEntry>() - 1;
+        let length = (self.header.length as usize).saturating_sub(offset);
+        let bytes = unsafe { slice::from_raw_parts(&self.processor_uid_string as *const u8, length) };
+        let bytes = match bytes.iter().position(|&byte| byte == 0) {
+            Some(end) => &bytes[..end],
+            None => bytes,
+        };
+
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+
+        str::from_utf8(bytes)
+            .map(|uid| Some(String::from(uid)))
+            .map_err(|_| AcpiError::InvalidMadt(MadtError::InvalidLocalSapicUidString))
+    }
+}
+
 #[repr(C, packed)]
 pub struct PlatformInterruptSourceEntry {
     header: EntryHeader,
@@ -453,13 +989,13 @@ pub struct GiccEntry {
     parking_protocol_version: u32,
     performance_interrupt_gsiv: u32,
     parked_address: u64,
-    gic_registers_address: u64,
-    gic_control_block_address: u64,
+    physical_base_address: u64,
+    gicv_base_address: u64,
+    gich_base_address: u64,
     vgic_maintenance_interrupt: u32,
     gicr_base_address: u64,
     mpidr: u64,
-    processor_power_efficiency_class: u8,
-    _reserved2: [u8; 3],
+    // Followed by the processor power efficiency class and three reserved bytes, from ACPI 6.0
 }
 
 #[repr(C, packed)]
@@ -509,6 +1045,73 @@ pub struct GicInterruptTranslationServiceEntry {
     _reserved2: u32,
 }
 
+/// Describes the mailbox used to wake up application processors on platforms that don't support INIT-SIPI-SIPI.
+#[repr(C, packed)]
+pub struct MultiprocessorWakeupEntry {
+    header: EntryHeader,
+    mailbox_version: u16,
+    _reserved: u32,
+    mailbox_address: u64,
+}
+
+/// Describes the local interrupt controller of a single RISC-V hart.
+#[repr(C, packed)]
+pub struct RiscVRintcEntry {
+    header: EntryHeader,
+    version: u8,
+    _reserved: u8,
+    flags: u32,
+    hart_id: u64,
+    processor_uid: u32,
+    external_interrupt_controller_id: u32,
+    imsic_address: u64,
+    imsic_size: u32,
+}
+
+/// Describes the properties common to all of the RISC-V Incoming MSI Controllers (IMSICs) on the system. The
+/// address of each hart's IMSIC is given by its RINTC entry.
+#[repr(C, packed)]
+pub struct RiscVImsicEntry {
+    header: EntryHeader,
+    version: u8,
+    _reserved: u8,
+    flags: u32,
+    num_ids: u16,
+    num_guest_ids: u16,
+    guest_index_bits: u8,
+    hart_index_bits: u8,
+    group_index_bits: u8,
+    group_index_shift: u8,
+}
+
+#[repr(C, packed)]
+pub struct RiscVAplicEntry {
+    header: EntryHeader,
+    version: u8,
+    aplic_id: u8,
+    flags: u32,
+    hardware_id: [u8; 8],
+    num_idcs: u16,
+    num_sources: u16,
+    global_system_interrupt_base: u32,
+    aplic_address: u64,
+    aplic_size: u32,
+}
+
+#[repr(C, packed)]
+pub struct RiscVPlicEntry {
+    header: EntryHeader,
+    version: u8,
+    plic_id: u8,
+    hardware_id: [u8; 8],
+    num_sources: u16,
+    max_priority: u16,
+    flags: u32,
+    plic_size: u32,
+    plic_address: u64,
+    global_system_interrupt_base: u32,
+}
+
 fn parse_mps_inti_flags(flags: u16) -> Result<(Polarity, TriggerMode), AcpiError> {
     let polarity = match flags.get_bits(0..2) {
         0b00 => Polarity::SameAsBus,
@@ -526,3 +1129,92 @@ fn parse_mps_inti_flags(flags: u16) -> Result<(Polarity, TriggerMode), AcpiError
 
     Ok((polarity, trigger_mode))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    /// Build an MADT of the given revision, containing the given entries (each including its header).
+    fn madt(revision: u8, entries: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = vec![0; mem::size_of::<Madt>()];
+        bytes[0..4].copy_from_slice(b"APIC");
+        bytes[8] = revision;
+        bytes[36..40].copy_from_slice(&0xfee0_0000u32.to_le_bytes());
+        for entry in entries {
+            bytes.extend_from_slice(entry);
+        }
+
+        let length = bytes.len() as u32;
+        bytes[4..8].copy_from_slice(&length.to_le_bytes());
+        bytes
+    }
+
+    /// Build a GICC entry of the given length, which is `76` for ACPI 5.1 entries and `80` from ACPI 6.0.
+    fn gicc(length: u8, processor_uid: u32, mpidr: u64) -> Vec<u8> {
+        let mut entry = vec![0; length as usize];
+        entry[0] = 0xb;
+        entry[1] = length;
+        entry[8..12].copy_from_slice(&processor_uid.to_le_bytes());
+        entry[12..16].copy_from_slice(&1u32.to_le_bytes());
+        entry[68..76].copy_from_slice(&mpidr.to_le_bytes());
+        if length >= 80 {
+            entry[76] = 2;
+        }
+        entry
+    }
+
+    #[test]
+    fn acpi_5_1_gicc() {
+        let bytes = madt(3, &[&gicc(76, 0, 0x0), &gicc(76, 1, 0x1_0000_0100)]);
+        let madt = unsafe { &*(bytes.as_ptr() as *const Madt) };
+
+        let (model, processor_info) = madt.parse_interrupt_model().unwrap();
+        let gic = match model {
+            InterruptModel::Gic(gic) => gic,
+            other => panic!("unexpected interrupt model: {:?}", other),
+        };
+        assert_eq!(gic.cpu_interfaces.len(), 2);
+        assert_eq!(gic.cpu_interfaces[1].processor_uid, 1);
+        assert_eq!(gic.cpu_interfaces[1].mpidr, 0x1_0000_0100);
+        assert_eq!(gic.cpu_interfaces[1].processor_power_efficiency_class, None);
+
+        let processor_info = processor_info.unwrap();
+        assert_eq!(processor_info.boot_processor.processor_uid, 0);
+        assert_eq!(processor_info.application_processors.len(), 1);
+        assert_eq!(processor_info.application_processors[0].local_apic_id, 0x0100_0100);
+    }
+
+    #[test]
+    fn acpi_6_gicc() {
+        let bytes = madt(5, &[&gicc(80, 0, 0x0)]);
+        let madt = unsafe { &*(bytes.as_ptr() as *const Madt) };
+
+        match madt.parse_interrupt_model().unwrap().0 {
+            InterruptModel::Gic(gic) => {
+                assert_eq!(gic.cpu_interfaces[0].processor_power_efficiency_class, Some(2))
+            }
+            other => panic!("unexpected interrupt model: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn no_usable_local_apic() {
+        /*
+         * The local APIC entry is too short, so it's returned as an unknown entry, leaving no processors.
+         */
+        let short_local_apic = [0x0, 4, 0, 0];
+        let mut io_apic = vec![0x1, 12, 0, 0];
+        io_apic.extend_from_slice(&0xfec0_0000u32.to_le_bytes());
+        io_apic.extend_from_slice(&0u32.to_le_bytes());
+        let bytes = madt(5, &[&short_local_apic, &io_apic]);
+        let madt = unsafe { &*(bytes.as_ptr() as *const Madt) };
+
+        let (model, processor_info) = madt.parse_interrupt_model().unwrap();
+        match model {
+            InterruptModel::Apic(apic) => assert_eq!(apic.io_apics.len(), 1),
+            other => panic!("unexpected interrupt model: {:?}", other),
+        }
+        assert!(processor_info.is_none());
+    }
+}
@@ -1,6 +1,13 @@
-use crate::{sdt::SdtHeader, AcpiError, AcpiHandler, AcpiTable, AcpiTables};
+use crate::{
+    sdt::{self, SdtHeader, Signature},
+    AcpiError,
+    AcpiHandler,
+    AcpiTable,
+    AcpiTables,
+    CheckedTable,
+};
 use alloc::vec::Vec;
-use core::{mem, slice};
+use core::{mem, ptr};
 
 /// Describes a set of regions of physical memory used to access the PCIe configuration space. A
 /// region is created for each entry in the MCFG. Given the segment group, bus, device number, and
@@ -17,12 +24,8 @@ impl PciConfigRegions {
     where
         H: AcpiHandler,
     {
-        let mcfg = unsafe {
-            tables
-                .get_sdt::<Mcfg>(crate::sdt::Signature::MCFG)?
-                .ok_or(AcpiError::TableMissing(crate::sdt::Signature::MCFG))?
-        };
-        Ok(PciConfigRegions { regions: mcfg.entries().iter().map(|&entry| entry).collect() })
+        let mcfg = tables.get_table::<Mcfg>()?.ok_or(AcpiError::TableMissing(Signature::MCFG))?;
+        Ok(PciConfigRegions { regions: mcfg.entries().collect() })
     }
 
     /// Get the physical address of the start of the configuration space for a given PCIe device
@@ -58,18 +61,17 @@ impl AcpiTable for Mcfg {
     }
 }
 
-impl Mcfg {
-    fn entries(&self) -> &[McfgEntry] {
-        let length = self.header.length as usize - mem::size_of::<Mcfg>();
-
-        // intentionally round down in case length isn't an exact multiple of McfgEntry size
-        let num_entries = length / mem::size_of::<McfgEntry>();
+unsafe impl CheckedTable for Mcfg {
+    const SIGNATURE: Signature = Signature::MCFG;
+}
 
-        unsafe {
-            let pointer =
-                (self as *const Mcfg as *const u8).offset(mem::size_of::<Mcfg>() as isize) as *const McfgEntry;
-            slice::from_raw_parts(pointer, num_entries)
-        }
+impl Mcfg {
+    fn entries(&self) -> impl Iterator<Item = McfgEntry> + '_ {
+        // `chunks_exact` intentionally ignores the remainder, in case the length isn't an exact multiple of the
+        // size of `McfgEntry`
+        unsafe { sdt::table_contents(self) }
+            .chunks_exact(mem::size_of::<McfgEntry>())
+            .map(|entry| unsafe { ptr::read_unaligned(entry.as_ptr() as *const McfgEntry) })
     }
 }
 
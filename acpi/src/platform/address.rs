@@ -2,6 +2,7 @@
 //! in a wide range of address spaces.
 
 use crate::AcpiError;
+use bit_field::BitField;
 
 /// This is the raw form of a Generic Address Structure, and follows the layout found in the ACPI tables. It does
 /// not form part of the public API, and should be turned into a `GenericAddress` for most use-cases.
@@ -49,6 +50,38 @@ pub enum AccessSize {
     QWordAccess,
 }
 
+/// Provides access to the address spaces that registers described by a `GenericAddress` can live in. This must be
+/// implemented by the user of the library to use [`GenericAddress::read`] and [`GenericAddress::write`]. Memory
+/// accesses are made to physical addresses, and should be performed with volatile accesses of exactly the
+/// requested width.
+pub trait RegisterAccess {
+    fn read_u8(&self, address: usize) -> u8;
+    fn read_u16(&self, address: usize) -> u16;
+    fn read_u32(&self, address: usize) -> u32;
+    fn read_u64(&self, address: usize) -> u64;
+
+    fn write_u8(&self, address: usize, value: u8);
+    fn write_u16(&self, address: usize, value: u16);
+    fn write_u32(&self, address: usize, value: u32);
+    fn write_u64(&self, address: usize, value: u64);
+
+    fn read_io_u8(&self, port: u16) -> u8;
+    fn read_io_u16(&self, port: u16) -> u16;
+    fn read_io_u32(&self, port: u16) -> u32;
+
+    fn write_io_u8(&self, port: u16, value: u8);
+    fn write_io_u16(&self, port: u16, value: u16);
+    fn write_io_u32(&self, port: u16, value: u32);
+
+    fn read_pci_u8(&self, segment: u16, bus: u8, device: u8, function: u8, offset: u16) -> u8;
+    fn read_pci_u16(&self, segment: u16, bus: u8, device: u8, function: u8, offset: u16) -> u16;
+    fn read_pci_u32(&self, segment: u16, bus: u8, device: u8, function: u8, offset: u16) -> u32;
+
+    fn write_pci_u8(&self, segment: u16, bus: u8, device: u8, function: u8, offset: u16, value: u8);
+    fn write_pci_u16(&self, segment: u16, bus: u8, device: u8, function: u8, offset: u16, value: u16);
+    fn write_pci_u32(&self, segment: u16, bus: u8, device: u8, function: u8, offset: u16, value: u32);
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct GenericAddress {
     pub address_space: AddressSpace,
@@ -95,3 +128,178 @@ impl GenericAddress {
         })
     }
 }
+
+impl GenericAddress {
+    /// Read the register described by this `GenericAddress`. The register is read using accesses of
+    /// `access_size`, and the value is shifted down by `bit_offset` and masked to `bit_width` bits. If the access
+    /// size is undefined, the smallest access that covers the whole register is used. Registers that are wider
+    /// than the access size are read with multiple accesses, starting at the lowest address.
+    ///
+    /// Only registers in system memory, system I/O, and PCI configuration space can be accessed.
+    pub fn read<R>(&self, access: &R) -> Result<u64, AcpiError>
+    where
+        R: RegisterAccess,
+    {
+        let (access_width, num_accesses) = self.accesses()?;
+
+        let mut value = 0;
+        for i in 0..num_accesses {
+            let part = self.read_part(access, i * (access_width / 8), access_width)?;
+            value.set_bits((i * access_width) as usize..((i + 1) * access_width) as usize, part);
+        }
+
+        Ok(value.get_bits(self.bit_offset as usize..(self.bit_offset + self.bit_width) as usize))
+    }
+
+    /// Write `value` to the register described by this `GenericAddress`. The value is masked to `bit_width` bits
+    /// and shifted up by `bit_offset`, and then written with accesses of `access_size`, in the same way as
+    /// [`GenericAddress::read`]. Any bits of the accessed region that are not part of the register are written as
+    /// zero - they are not read and preserved, as some registers have bits that are cleared by writing ones.
+    pub fn write<R>(&self, access: &R, value: u64) -> Result<(), AcpiError>
+    where
+        R: RegisterAccess,
+    {
+        let (access_width, num_accesses) = self.accesses()?;
+
+        let mut raw = 0;
+        raw.set_bits(
+            self.bit_offset as usize..(self.bit_offset + self.bit_width) as usize,
+            value.get_bits(0..self.bit_width as usize),
+        );
+
+        for i in 0..num_accesses {
+            let part = raw.get_bits((i * access_width) as usize..((i + 1) * access_width) as usize);
+            self.write_part(access, i * (access_width / 8), access_width, part)?;
+        }
+
+        Ok(())
+    }
+
+    /// Work out the width (in bits) of each access made to the register, and how many accesses are needed to cover
+    /// the whole register.
+    fn accesses(&self) -> Result<(u64, u64), AcpiError> {
+        let max_width = match self.address_space {
+            AddressSpace::SystemMemory => 64,
+            AddressSpace::SystemIo | AddressSpace::PciConfigSpace => 32,
+            other => return Err(AcpiError::UnsupportedAddressSpace(other)),
+        };
+
+        let register_width = self.bit_offset as u64 + self.bit_width as u64;
+        if self.bit_width == 0 || register_width > 64 {
+            return Err(AcpiError::InvalidGenericAddress);
+        }
+
+        let access_width = match self.access_size {
+            AccessSize::ByteAccess => 8,
+            AccessSize::WordAccess => 16,
+            AccessSize::DWordAccess => 32,
+            AccessSize::QWordAccess => 64,
+            AccessSize::Undefined => u64::min(u64::max(register_width.next_power_of_two(), 8), max_width),
+        };
+        if access_width > max_width {
+            return Err(AcpiError::InvalidGenericAddress);
+        }
+
+        Ok((access_width, register_width.div_ceil(access_width)))
+    }
+
+    fn read_part<R>(&self, access: &R, offset: u64, width: u64) -> Result<u64, AcpiError>
+    where
+        R: RegisterAccess,
+    {
+        let address = self.address.checked_add(offset).ok_or(AcpiError::InvalidGenericAddress)?;
+
+        match self.address_space {
+            AddressSpace::SystemMemory => {
+                let address = address as usize;
+                Ok(match width {
+                    8 => access.read_u8(address) as u64,
+                    16 => access.read_u16(address) as u64,
+                    32 => access.read_u32(address) as u64,
+                    64 => access.read_u64(address),
+                    _ => unreachable!(),
+                })
+            }
+            AddressSpace::SystemIo => {
+                let port = io_port(address)?;
+                Ok(match width {
+                    8 => access.read_io_u8(port) as u64,
+                    16 => access.read_io_u16(port) as u64,
+                    32 => access.read_io_u32(port) as u64,
+                    _ => unreachable!(),
+                })
+            }
+            AddressSpace::PciConfigSpace => {
+                let (device, function, offset) = pci_address(address)?;
+                Ok(match width {
+                    8 => access.read_pci_u8(0, 0, device, function, offset) as u64,
+                    16 => access.read_pci_u16(0, 0, device, function, offset) as u64,
+                    32 => access.read_pci_u32(0, 0, device, function, offset) as u64,
+                    _ => unreachable!(),
+                })
+            }
+            other => Err(AcpiError::UnsupportedAddressSpace(other)),
+        }
+    }
+
+    fn write_part<R>(&self, access: &R, offset: u64, width: u64, value: u64) -> Result<(), AcpiError>
+    where
+        R: RegisterAccess,
+    {
+        let address = self.address.checked_add(offset).ok_or(AcpiError::InvalidGenericAddress)?;
+
+        match self.address_space {
+            AddressSpace::SystemMemory => {
+                let address = address as usize;
+                match width {
+                    8 => access.write_u8(address, value as u8),
+                    16 => access.write_u16(address, value as u16),
+                    32 => access.write_u32(address, value as u32),
+                    64 => access.write_u64(address, value),
+                    _ => unreachable!(),
+                }
+            }
+            AddressSpace::SystemIo => {
+                let port = io_port(address)?;
+                match width {
+                    8 => access.write_io_u8(port, value as u8),
+                    16 => access.write_io_u16(port, value as u16),
+                    32 => access.write_io_u32(port, value as u32),
+                    _ => unreachable!(),
+                }
+            }
+            AddressSpace::PciConfigSpace => {
+                let (device, function, offset) = pci_address(address)?;
+                match width {
+                    8 => access.write_pci_u8(0, 0, device, function, offset, value as u8),
+                    16 => access.write_pci_u16(0, 0, device, function, offset, value as u16),
+                    32 => access.write_pci_u32(0, 0, device, function, offset, value as u32),
+                    _ => unreachable!(),
+                }
+            }
+            other => return Err(AcpiError::UnsupportedAddressSpace(other)),
+        }
+
+        Ok(())
+    }
+}
+
+fn io_port(address: u64) -> Result<u16, AcpiError> {
+    if address > u16::MAX as u64 {
+        return Err(AcpiError::InvalidGenericAddress);
+    }
+
+    Ok(address as u16)
+}
+
+/// Split an address in PCI configuration space into its device, function, and offset. See
+/// [`AddressSpace::PciConfigSpace`] for the format.
+fn pci_address(address: u64) -> Result<(u8, u8, u16), AcpiError> {
+    let device = address.get_bits(32..48);
+    let function = address.get_bits(16..32);
+    if address.get_bits(48..64) != 0 || device > 0x1f || function > 0x7 {
+        return Err(AcpiError::InvalidGenericAddress);
+    }
+
+    Ok((device as u8, function as u8, address.get_bits(0..16) as u16))
+}
@@ -1,4 +1,4 @@
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 
 #[derive(Debug)]
 pub struct IoApic {
@@ -76,6 +76,212 @@ pub struct Apic {
     pub also_has_legacy_pics: bool,
 }
 
+#[derive(Debug)]
+pub struct IoSapic {
+    pub id: u8,
+    pub address: u64,
+    pub global_system_interrupt_base: u32,
+}
+
+#[derive(Debug)]
+pub struct LocalSapic {
+    pub processor_id: u8,
+    pub local_sapic_id: u8,
+    pub local_sapic_eid: u8,
+    pub enabled: bool,
+    pub processor_uid: u32,
+    /// Used to associate this local SAPIC with a processor defined in the namespace when its `_UID` object is a
+    /// string, rather than an integer.
+    pub processor_uid_string: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlatformInterruptType {
+    /// Platform Management Interrupt
+    Pmi,
+    Init,
+    /// Corrected Platform Error Interrupt (CPEI)
+    CorrectedPlatformError,
+    Reserved(u8),
+}
+
+/// Describes a platform interrupt source that is routed through an I/O SAPIC. OSPM must program the I/O SAPIC
+/// redirection table entry for `global_system_interrupt` to deliver `io_sapic_vector` to the given processor.
+#[derive(Debug)]
+pub struct PlatformInterruptSource {
+    pub interrupt_type: PlatformInterruptType,
+    pub polarity: Polarity,
+    pub trigger_mode: TriggerMode,
+    pub processor_id: u8,
+    pub processor_eid: u8,
+    pub io_sapic_vector: u8,
+    pub global_system_interrupt: u32,
+    /// If this is set for a `CorrectedPlatformError` source, the CPEI should be delivered to the processor given
+    /// here, rather than being distributed according to the processors' `_CPE` objects.
+    pub cpei_processor_override: bool,
+}
+
+#[derive(Debug)]
+pub struct Sapic {
+    pub local_sapic_address: u64,
+    pub io_sapics: Vec<IoSapic>,
+    pub local_sapics: Vec<LocalSapic>,
+    pub platform_interrupt_sources: Vec<PlatformInterruptSource>,
+    pub local_sapic_nmi_lines: Vec<NmiLine>,
+    pub interrupt_source_overrides: Vec<InterruptSourceOverride>,
+    pub nmi_sources: Vec<NmiSource>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GicVersion {
+    /// The version of the GIC is not described by the MADT, and should be discovered from the hardware itself.
+    HardwareDiscovery,
+    V1,
+    V2,
+    V3,
+    V4,
+    Reserved(u8),
+}
+
+/// Describes the GIC Distributor (GICD). There is only one of these on a system.
+#[derive(Debug)]
+pub struct GicDistributor {
+    pub id: u32,
+    pub physical_base_address: u64,
+    pub version: GicVersion,
+}
+
+/// Describes the GIC CPU interface of a single processor. On systems with a GICv3 or later, most of the register
+/// addresses will be zero, as the CPU interface is accessed through system registers instead.
+#[derive(Debug)]
+pub struct GicCpuInterface {
+    pub cpu_interface_number: u32,
+    pub processor_uid: u32,
+    pub enabled: bool,
+    /// The value of the processor's `MPIDR_EL1` register. Only the affinity fields are populated.
+    pub mpidr: u64,
+    pub parking_protocol_version: u32,
+    /// The physical address of the processor's mailbox for the ARM Parking Protocol, or `0` if the processor does
+    /// not support it.
+    pub parked_address: u64,
+    /// The GSIV of the processor's Performance Monitoring Unit (PMU) overflow interrupt.
+    pub performance_interrupt_gsiv: u32,
+    pub performance_interrupt_trigger_mode: TriggerMode,
+    pub vgic_maintenance_interrupt: u32,
+    pub vgic_maintenance_interrupt_trigger_mode: TriggerMode,
+    /// The physical address of the GIC CPU interface registers (GICC).
+    pub physical_base_address: u64,
+    /// The physical address of the GIC virtual CPU interface registers (GICV).
+    pub gicv_base_address: u64,
+    /// The physical address of the GIC virtual interface control block registers (GICH).
+    pub gich_base_address: u64,
+    /// The physical address of the processor's GIC Redistributor. This is only used on GICv3+ systems where the
+    /// redistributors are not in an always-on power domain, and is otherwise `0` (the redistributors should then
+    /// be found from the `redistributors` discovery ranges).
+    pub gicr_base_address: u64,
+    /// Describes the relative power efficiency of the processor, where a lower value is more efficient. This is
+    /// `None` if the GICC entry is from before ACPI 6.0, and so doesn't have this field.
+    pub processor_power_efficiency_class: Option<u8>,
+}
+
+/// Describes a range of memory that GIC Redistributors can be discovered from.
+#[derive(Debug)]
+pub struct GicRedistributor {
+    pub discovery_range_base_address: u64,
+    pub discovery_range_length: u32,
+}
+
+/// Describes a GIC Interrupt Translation Service (ITS), which translates MSIs into LPIs on GICv3+ systems.
+#[derive(Debug)]
+pub struct GicIts {
+    pub id: u32,
+    pub physical_base_address: u64,
+}
+
+/// Describes a GICv2m MSI frame, which allows MSIs to be translated into SPIs.
+#[derive(Debug)]
+pub struct GicMsiFrame {
+    pub id: u32,
+    pub physical_base_address: u64,
+    /// If this is `false`, `spi_count` and `spi_base` should be ignored, and the values should instead be read
+    /// from the frame's `MSI_TYPER` register.
+    pub spi_count_and_base_valid: bool,
+    pub spi_count: u16,
+    pub spi_base: u16,
+}
+
+#[derive(Debug)]
+pub struct Gic {
+    /// The distributor of the system. This is `None` if the MADT does not contain a GICD entry.
+    pub distributor: Option<GicDistributor>,
+    pub cpu_interfaces: Vec<GicCpuInterface>,
+    pub redistributors: Vec<GicRedistributor>,
+    pub interrupt_translation_services: Vec<GicIts>,
+    pub msi_frames: Vec<GicMsiFrame>,
+}
+
+/// Describes the local interrupt controller (RINTC) of a RISC-V hart.
+#[derive(Debug)]
+pub struct RiscVRintc {
+    pub hart_id: u64,
+    pub processor_uid: u32,
+    pub enabled: bool,
+    /// Identifies the APLIC or PLIC that delivers external interrupts to this hart, if the hart doesn't have an
+    /// IMSIC. Bits `24..32` contain the ID of the APLIC or PLIC, and bits `0..16` contain the index of the
+    /// APLIC's interrupt delivery control (IDC) structure or the PLIC's context.
+    pub external_interrupt_controller_id: u32,
+    /// The physical address of the hart's IMSIC interrupt files, or `0` if it doesn't have an IMSIC.
+    pub imsic_address: u64,
+    pub imsic_size: u32,
+}
+
+/// Describes the geometry shared by all of the Incoming MSI Controllers (IMSICs) on the system.
+#[derive(Debug)]
+pub struct RiscVImsic {
+    /// The number of interrupt identities supported by each supervisor-level interrupt file.
+    pub num_ids: u16,
+    /// The number of interrupt identities supported by each guest interrupt file.
+    pub num_guest_ids: u16,
+    pub guest_index_bits: u8,
+    pub hart_index_bits: u8,
+    pub group_index_bits: u8,
+    pub group_index_shift: u8,
+}
+
+/// Describes a RISC-V Advanced Platform-Level Interrupt Controller (APLIC).
+#[derive(Debug)]
+pub struct RiscVAplic {
+    pub id: u8,
+    pub hardware_id: [u8; 8],
+    /// The number of interrupt delivery control (IDC) structures, or `0` if the APLIC forwards interrupts as MSIs.
+    pub num_idcs: u16,
+    pub num_sources: u16,
+    pub global_system_interrupt_base: u32,
+    pub address: u64,
+    pub size: u32,
+}
+
+/// Describes a RISC-V Platform-Level Interrupt Controller (PLIC).
+#[derive(Debug)]
+pub struct RiscVPlic {
+    pub id: u8,
+    pub hardware_id: [u8; 8],
+    pub num_sources: u16,
+    pub max_priority: u16,
+    pub global_system_interrupt_base: u32,
+    pub address: u64,
+    pub size: u32,
+}
+
+#[derive(Debug)]
+pub struct RiscV {
+    pub harts: Vec<RiscVRintc>,
+    /// Describes the IMSICs of the system. This is `None` if the harts do not have IMSICs.
+    pub imsic: Option<RiscVImsic>,
+    pub aplics: Vec<RiscVAplic>,
+    pub plics: Vec<RiscVPlic>,
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum InterruptModel {
@@ -87,4 +293,19 @@ pub enum InterruptModel {
     /// Controllers. These are likely to be found on x86 and x86_64 systems and are made up of a
     /// Local APIC for each core and one or more I/O APICs to handle external interrupts.
     Apic(Apic),
+
+    /// Describes an interrupt controller based around the Streamlined Advanced Programmable Interrupt Controllers.
+    /// These are found on Itanium systems, and are made up of a Local SAPIC for each processor and one or more I/O
+    /// SAPICs to handle external interrupts.
+    Sapic(Sapic),
+
+    /// Describes an interrupt controller based around the Generic Interrupt Controller (GIC). This is used on
+    /// ARM systems, and is made up of a CPU interface for each processor, a Distributor, and (from GICv3 onwards)
+    /// Redistributors and Interrupt Translation Services. The `Processor`s of a GIC system are identified by the
+    /// affinity fields of their MPIDRs, and the first processor listed by the MADT is assumed to be the BSP.
+    Gic(Gic),
+
+    /// Describes the interrupt controllers of a RISC-V system. Each hart has a local interrupt controller (RINTC),
+    /// and external interrupts are delivered either as MSIs through per-hart IMSICs, or by APLICs or PLICs.
+    RiscV(RiscV),
 }
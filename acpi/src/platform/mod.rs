@@ -1,22 +1,51 @@
 pub mod address;
 pub mod interrupt;
+pub mod mp_wakeup;
 
-use address::GenericAddress;
-use bit_field::BitField;
+use address::{AddressSpace, GenericAddress, RegisterAccess};
 pub use interrupt::{
     Apic,
+    Gic,
+    GicCpuInterface,
+    GicDistributor,
+    GicIts,
+    GicMsiFrame,
+    GicRedistributor,
+    GicVersion,
     InterruptModel,
     InterruptSourceOverride,
     IoApic,
+    IoSapic,
     LocalInterruptLine,
+    LocalSapic,
     NmiLine,
     NmiProcessor,
     NmiSource,
+    PlatformInterruptSource,
+    PlatformInterruptType,
     Polarity,
+    RiscV,
+    RiscVAplic,
+    RiscVImsic,
+    RiscVPlic,
+    RiscVRintc,
+    Sapic,
     TriggerMode,
 };
+pub use mp_wakeup::{MultiprocessorWakeup, MultiprocessorWakeupMailbox};
 
-use crate::{fadt::Fadt, madt::Madt, AcpiError, AcpiHandler, AcpiTables, PowerProfile};
+use crate::{
+    fadt::Fadt,
+    madt::Madt,
+    AcpiError,
+    AcpiHandler,
+    AcpiTables,
+    ArmBootArchFlags,
+    FixedFeatureFlags,
+    HardwareModel,
+    IaPcBootArchFlags,
+    PowerProfile,
+};
 use alloc::vec::Vec;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -25,7 +54,8 @@ pub enum ProcessorState {
     Disabled,
 
     /// A processor waiting for a SIPI (Startup Inter-processor Interrupt) is currently not active,
-    /// but may be brought up.
+    /// but may be brought up. On GIC systems, this means the processor may be brought up using PSCI or the
+    /// Parking Protocol.
     WaitingForSipi,
 
     /// A Running processor is currently brought up and running code.
@@ -34,8 +64,15 @@ pub enum ProcessorState {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Processor {
-    pub processor_uid: u8,
-    pub local_apic_id: u8,
+    /// Corresponds to the `_UID` object of the processor's `Device`, or the `ProcessorId` field of the `Processor`
+    /// object, in AML. This is `u8`-sized for processors described by local APIC entries, but can be any `u32` for
+    /// processors described by x2APIC entries.
+    pub processor_uid: u32,
+    /// The ID of the local APIC of the processor. This is a 32-bit x2APIC ID if the processor was described by a
+    /// local x2APIC entry, and an 8-bit xAPIC ID otherwise. On GIC systems, this is instead the affinity fields
+    /// of the processor's MPIDR, with `Aff3` in bits `24..32` and `Aff2..Aff0` in bits `0..24`, while on SAPIC
+    /// systems it is the processor's local SAPIC ID and EID, in bits `8..16` and `0..8` respectively.
+    pub local_apic_id: u32,
 
     /// The state of this processor. Always check that the processor is not `Disabled` before
     /// attempting to bring it up!
@@ -43,7 +80,9 @@ pub struct Processor {
 
     /// Whether this processor is the Bootstrap Processor (BSP), or an Application Processor (AP).
     /// When the bootloader is entered, the BSP is the only processor running code. To run code on
-    /// more than one processor, you need to "bring up" the APs.
+    /// more than one processor, you need to "bring up" the APs. On GIC systems, the MADT does not say which
+    /// processor is the BSP, so the first processor listed is assumed to be - compare its ID with the MPIDR of
+    /// the running processor if this matters.
     pub is_ap: bool,
 }
 
@@ -51,6 +90,9 @@ pub struct ProcessorInfo {
     pub boot_processor: Processor,
     /// Application processors should be brought up in the order they're defined in this list.
     pub application_processors: Vec<Processor>,
+    /// If this is present, application processors must be woken up using the Multiprocessor Wakeup mailbox, and
+    /// not with INIT-SIPI-SIPI. This is the case for confidential computing guests, such as those using Intel TDX.
+    pub multiprocessor_wakeup: Option<MultiprocessorWakeup>,
 }
 
 /// Information about the ACPI Power Management Timer (ACPI PM Timer).
@@ -62,16 +104,160 @@ pub struct PmTimer {
     pub supports_32bit: bool,
 }
 impl PmTimer {
-    /// Creates a new instance of `PmTimer`.
+    /// The frequency of the PM timer, in Hz.
+    pub const FREQUENCY: u64 = 3_579_545;
+
+    /// Creates a new instance of `PmTimer`. Hardware-reduced platforms do not have a PM timer, so this always
+    /// returns `None` on them.
     pub fn new(fadt: &Fadt) -> Result<Option<PmTimer>, AcpiError> {
+        if fadt.hardware_model() == HardwareModel::Reduced {
+            return Ok(None);
+        }
+
         let base = fadt.pm_timer_block()?;
-        let flags = fadt.flags;
+        let flags = fadt.feature_flags();
 
         match base {
-            Some(base) => Ok(Some(PmTimer { base, supports_32bit: flags.get_bit(8) })),
+            Some(base) => Ok(Some(PmTimer { base, supports_32bit: flags.pm_timer_is_32bit() })),
             None => Ok(None),
         }
     }
+
+    /// Read the current value of the counter. This is a 24-bit or 32-bit value, depending on `supports_32bit`,
+    /// that wraps around when it overflows.
+    pub fn read<R>(&self, access: &R) -> Result<u32, AcpiError>
+    where
+        R: RegisterAccess,
+    {
+        Ok(self.base.read(access)? as u32 & self.counter_mask())
+    }
+
+    /// Calculate the number of ticks that have passed between two readings of the counter, taking into account
+    /// that the counter may have wrapped around (once) between them.
+    pub fn ticks_between(&self, start: u32, end: u32) -> u32 {
+        end.wrapping_sub(start) & self.counter_mask()
+    }
+
+    /// Convert a number of ticks of the PM timer into nanoseconds.
+    pub fn ticks_to_nanos(ticks: u64) -> u64 {
+        (ticks as u128 * 1_000_000_000 / PmTimer::FREQUENCY as u128) as u64
+    }
+
+    /// Convert a number of nanoseconds into ticks of the PM timer, rounding up.
+    pub fn nanos_to_ticks(nanos: u64) -> u64 {
+        (nanos as u128 * PmTimer::FREQUENCY as u128).div_ceil(1_000_000_000) as u64
+    }
+
+    /// Spin until at least `nanos` nanoseconds have passed, as measured by the PM timer. Waits that are longer
+    /// than the counter takes to wrap around are supported, as the counter is polled much more often than that.
+    pub fn busy_wait<R>(&self, access: &R, nanos: u64) -> Result<(), AcpiError>
+    where
+        R: RegisterAccess,
+    {
+        let target = PmTimer::nanos_to_ticks(nanos);
+        let mut elapsed = 0;
+        let mut last = self.read(access)?;
+
+        while elapsed < target {
+            core::hint::spin_loop();
+
+            let now = self.read(access)?;
+            elapsed += self.ticks_between(last, now) as u64;
+            last = now;
+        }
+
+        Ok(())
+    }
+
+    fn counter_mask(&self) -> u32 {
+        if self.supports_32bit {
+            0xffff_ffff
+        } else {
+            0x00ff_ffff
+        }
+    }
+}
+
+/// Describes one of the fixed hardware register blocks that are not implemented on hardware-reduced platforms.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FixedRegisterBlock {
+    Present(GenericAddress),
+    /// The FADT does not describe this register block.
+    NotPresent,
+    /// The platform uses the hardware-reduced ACPI model, which does not implement this register block. Any
+    /// address in the FADT has been ignored.
+    NotPresentInReducedMode,
+}
+
+impl FixedRegisterBlock {
+    pub fn address(&self) -> Option<GenericAddress> {
+        match self {
+            FixedRegisterBlock::Present(address) => Some(*address),
+            _ => None,
+        }
+    }
+}
+
+/// The fixed hardware registers described by the FADT. The register blocks that are not implemented on
+/// hardware-reduced platforms are described by `FixedRegisterBlock`s, while the remaining registers are `None` if
+/// the FADT does not provide them.
+#[derive(Clone, Debug)]
+pub struct FixedHardware {
+    pub pm1a_event_block: FixedRegisterBlock,
+    pub pm1b_event_block: FixedRegisterBlock,
+    pub pm1a_control_block: FixedRegisterBlock,
+    pub pm1b_control_block: FixedRegisterBlock,
+    pub pm2_control_block: FixedRegisterBlock,
+    pub gpe0_block: FixedRegisterBlock,
+    pub gpe1_block: FixedRegisterBlock,
+    /// The length of the GPE0 block in bytes. This should be used in preference to the width of `gpe0_block`, as
+    /// the block may be too long for its width to be described by a `GenericAddress`.
+    pub gpe0_block_length: u8,
+    pub gpe1_block_length: u8,
+    /// The offset within the General-Purpose Events that the GPE1 block's events start at.
+    pub gpe1_base: u8,
+    pub reset_register: Option<GenericAddress>,
+    /// The value that should be written to `reset_register` to reset the system.
+    pub reset_value: u8,
+    pub sleep_control_register: Option<GenericAddress>,
+    pub sleep_status_register: Option<GenericAddress>,
+}
+
+impl FixedHardware {
+    pub fn new(fadt: &Fadt) -> Result<FixedHardware, AcpiError> {
+        /*
+         * On hardware-reduced platforms, the fixed hardware fields of the FADT should be zero, but may contain
+         * anything, so we don't try to interpret them.
+         */
+        let reduced = fadt.hardware_model() == HardwareModel::Reduced;
+        let block = |address: fn(&Fadt) -> Result<Option<GenericAddress>, AcpiError>| {
+            if reduced {
+                return Ok(FixedRegisterBlock::NotPresentInReducedMode);
+            }
+
+            Ok(match address(fadt)? {
+                Some(address) => FixedRegisterBlock::Present(address),
+                None => FixedRegisterBlock::NotPresent,
+            })
+        };
+
+        Ok(FixedHardware {
+            pm1a_event_block: block(Fadt::pm1a_event_block)?,
+            pm1b_event_block: block(Fadt::pm1b_event_block)?,
+            pm1a_control_block: block(Fadt::pm1a_control_block)?,
+            pm1b_control_block: block(Fadt::pm1b_control_block)?,
+            pm2_control_block: block(Fadt::pm2_control_block)?,
+            gpe0_block: block(Fadt::gpe0_block)?,
+            gpe1_block: block(Fadt::gpe1_block)?,
+            gpe0_block_length: fadt.gpe0_block_length(),
+            gpe1_block_length: fadt.gpe1_block_length(),
+            gpe1_base: fadt.gpe1_base(),
+            reset_register: fadt.reset_register()?,
+            reset_value: fadt.reset_value(),
+            sleep_control_register: fadt.sleep_control_register()?,
+            sleep_status_register: fadt.sleep_status_register()?,
+        })
+    }
 }
 
 /// `PlatformInfo` allows the collection of some basic information about the platform from some of the fixed-size
@@ -79,14 +265,17 @@ impl PmTimer {
 /// about the processors and interrupt controllers on a platform.
 pub struct PlatformInfo {
     pub power_profile: PowerProfile,
+    pub hardware_model: HardwareModel,
+    pub feature_flags: FixedFeatureFlags,
+    pub iapc_boot_arch: IaPcBootArchFlags,
+    pub arm_boot_arch: ArmBootArchFlags,
     pub interrupt_model: InterruptModel,
-    /// On `x86_64` platforms that support the APIC, the processor topology must also be inferred from the
-    /// interrupt model. That information is stored here, if present.
+    /// On `x86_64` platforms that support the APIC, and on ARM platforms that use the GIC, the processor topology
+    /// must also be inferred from the interrupt model. That information is stored here, if present. On RISC-V, the
+    /// harts are instead listed by the interrupt model.
     pub processor_info: Option<ProcessorInfo>,
     pub pm_timer: Option<PmTimer>,
-    /*
-     * TODO: we could provide a nice view of the hardware register blocks in the FADT here.
-     */
+    pub fixed_hardware: FixedHardware,
 }
 
 impl PlatformInfo {
@@ -94,20 +283,59 @@ impl PlatformInfo {
     where
         H: AcpiHandler,
     {
-        let fadt = unsafe {
-            tables
-                .get_sdt::<Fadt>(crate::sdt::Signature::FADT)?
-                .ok_or(AcpiError::TableMissing(crate::sdt::Signature::FADT))?
-        };
+        let fadt = tables.get_table::<Fadt>()?.ok_or(AcpiError::TableMissing(crate::sdt::Signature::FADT))?;
         let power_profile = fadt.power_profile();
+        let hardware_model = fadt.hardware_model();
+        let feature_flags = fadt.feature_flags();
+        let iapc_boot_arch = fadt.iapc_boot_arch();
+        let arm_boot_arch = fadt.arm_boot_arch();
 
-        let madt = unsafe { tables.get_sdt::<Madt>(crate::sdt::Signature::MADT)? };
+        let madt = tables.get_table::<Madt>()?;
         let (interrupt_model, processor_info) = match madt {
             Some(madt) => madt.parse_interrupt_model()?,
             None => (InterruptModel::Unknown, None),
         };
         let pm_timer = PmTimer::new(&fadt)?;
+        let fixed_hardware = FixedHardware::new(&fadt)?;
+
+        Ok(PlatformInfo {
+            power_profile,
+            hardware_model,
+            feature_flags,
+            iapc_boot_arch,
+            arm_boot_arch,
+            interrupt_model,
+            processor_info,
+            pm_timer,
+            fixed_hardware,
+        })
+    }
+
+    /// Reset the system by writing the reset value to the reset register described by the FADT. Returns
+    /// `AcpiError::ResetUnsupported` if the platform does not support resetting through the reset register. The
+    /// reset may not take effect immediately, so if this returns `Ok`, the caller should wait for a short time
+    /// before falling back to another method of resetting the system.
+    pub fn reset<R>(&self, access: &R) -> Result<(), AcpiError>
+    where
+        R: RegisterAccess,
+    {
+        if !self.feature_flags.reset_register_supported() {
+            return Err(AcpiError::ResetUnsupported);
+        }
+        let reset_register = self.fixed_hardware.reset_register.ok_or(AcpiError::ResetUnsupported)?;
+
+        /*
+         * The reset register can only be in system memory, system I/O, or the configuration space of a PCI device
+         * on bus 0, and must be an 8-bit register.
+         */
+        match reset_register.address_space {
+            AddressSpace::SystemMemory | AddressSpace::SystemIo | AddressSpace::PciConfigSpace => (),
+            other => return Err(AcpiError::UnsupportedAddressSpace(other)),
+        }
+        if reset_register.bit_width != 8 || reset_register.bit_offset != 0 {
+            return Err(AcpiError::InvalidGenericAddress);
+        }
 
-        Ok(PlatformInfo { power_profile, interrupt_model, processor_info, pm_timer })
+        reset_register.write(access, self.fixed_hardware.reset_value as u64)
     }
 }
@@ -0,0 +1,89 @@
+//! Some platforms, such as confidential computing guests, do not support starting application processors with
+//! INIT-SIPI-SIPI. Instead, the firmware parks them in a loop that polls a shared mailbox, which the OS writes to
+//! in order to wake each processor up. The location of this mailbox is described by the MADT.
+
+use crate::{AcpiHandler, PhysicalMapping};
+use core::{
+    mem,
+    ptr,
+    sync::atomic::{fence, Ordering},
+};
+
+/// The location and version of the Multiprocessor Wakeup mailbox, as described by the MADT.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MultiprocessorWakeup {
+    pub mailbox_version: u16,
+    /// The physical address of the mailbox. This is always 4KiB-aligned.
+    pub mailbox_address: u64,
+}
+
+const COMMAND_NOOP: u16 = 0;
+const COMMAND_WAKEUP: u16 = 1;
+
+/// The part of the mailbox that is written by the OS. The rest of the 4KiB page is reserved for the OS and the
+/// firmware.
+#[repr(C)]
+struct Mailbox {
+    command: u16,
+    _reserved: u16,
+    apic_id: u32,
+    wakeup_vector: u64,
+}
+
+/// A mapping of the Multiprocessor Wakeup mailbox, which can be used to wake up application processors.
+pub struct MultiprocessorWakeupMailbox<H>
+where
+    H: AcpiHandler,
+{
+    mapping: PhysicalMapping<H, Mailbox>,
+}
+
+impl<H> MultiprocessorWakeupMailbox<H>
+where
+    H: AcpiHandler,
+{
+    /// Map the mailbox described by `wakeup`.
+    ///
+    /// ### Safety
+    /// `wakeup` must describe the mailbox of the platform the library is running on (i.e. it should come from the
+    /// MADT), and nothing else must be accessing the mailbox while this type exists.
+    pub unsafe fn new(handler: H, wakeup: MultiprocessorWakeup) -> MultiprocessorWakeupMailbox<H> {
+        let mapping = unsafe {
+            handler.map_physical_region::<Mailbox>(wakeup.mailbox_address as usize, mem::size_of::<Mailbox>())
+        };
+        MultiprocessorWakeupMailbox { mapping }
+    }
+
+    /// Returns `true` if the mailbox can accept a new command. After a command is written, the firmware resets
+    /// the mailbox once the target processor has received it, so this can also be polled to wait for a processor
+    /// to acknowledge being woken up.
+    pub fn is_ready(&self) -> bool {
+        let mailbox = self.mapping.virtual_start.as_ptr();
+        unsafe { ptr::read_volatile(ptr::addr_of!((*mailbox).command)) == COMMAND_NOOP }
+    }
+
+    /// Ask the processor with the given APIC ID to start executing at `wakeup_vector`, which is a physical
+    /// address. The processor will be in 64-bit mode with paging identity-mapping the vector when it jumps there.
+    /// Returns `false` without writing anything if the mailbox is still busy with a previous command.
+    #[must_use]
+    pub fn wake_processor(&mut self, apic_id: u32, wakeup_vector: u64) -> bool {
+        if !self.is_ready() {
+            return false;
+        }
+
+        let mailbox = self.mapping.virtual_start.as_ptr();
+        unsafe {
+            ptr::write_volatile(ptr::addr_of_mut!((*mailbox).apic_id), apic_id);
+            ptr::write_volatile(ptr::addr_of_mut!((*mailbox).wakeup_vector), wakeup_vector);
+
+            /*
+             * The firmware may act on the command as soon as it's written, so the APIC ID and wakeup vector must
+             * be visible to the application processors first.
+             */
+            fence(Ordering::SeqCst);
+            ptr::write_volatile(ptr::addr_of_mut!((*mailbox).command), COMMAND_WAKEUP);
+        }
+
+        true
+    }
+}
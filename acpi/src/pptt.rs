@@ -0,0 +1,603 @@
+use crate::{
+    platform::Processor,
+    sdt::{self, SdtHeader, Signature, SubtableFormat, SubtableIter},
+    AcpiError,
+    AcpiHandler,
+    AcpiTable,
+    AcpiTables,
+    CheckedTable,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use bit_field::BitField;
+use core::mem;
+
+#[derive(Debug)]
+pub enum PpttError {
+    /// A structure refers to another by an offset that does not point to a structure of the correct type, or the
+    /// parents of the processor hierarchy nodes form a cycle.
+    InvalidReference,
+    /// A processor hierarchy node is too short to hold the number of private resources it claims to have.
+    InvalidPrivateResourceCount,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NodeKind {
+    Package,
+    /// A grouping of cores below the package level. There may be several levels of clusters.
+    Cluster,
+    Core,
+    Thread,
+}
+
+/// A node in the processor hierarchy. Nodes refer to each other, and to their caches, by their index within the
+/// `ProcessorTopology`.
+#[derive(Clone, Debug)]
+pub struct ProcessorNode {
+    pub kind: NodeKind,
+    /// The ACPI processor UID of this node. For leaf nodes, this matches the `processor_uid` of a processor in the
+    /// MADT. For other nodes, this matches the `_UID` of a processor container device, if present.
+    pub processor_uid: Option<u32>,
+    /// If this is set, all of the children of this node have an identical implementation.
+    pub identical_implementation: bool,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    /// The caches private to this node. Each may be followed by further caches, through `Cache::next_level`.
+    pub private_caches: Vec<usize>,
+    pub ids: Vec<ProcessorIdentification>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CacheType {
+    Data,
+    Instruction,
+    Unified,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WritePolicy {
+    WriteBack,
+    WriteThrough,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AllocationType {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// Describes a cache. Each property is `None` if the PPTT does not provide it, in which case it should be
+/// discovered from the processor itself.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    /// The level of the cache, where the first caches found above a leaf node are level `1`. This is `None` if the
+    /// cache can't be reached from a leaf node.
+    pub level: Option<u8>,
+    /// The size of the cache, in bytes.
+    pub size: Option<u32>,
+    pub sets: Option<u32>,
+    pub associativity: Option<u8>,
+    /// The size of a cache line, in bytes.
+    pub line_size: Option<u16>,
+    pub cache_type: Option<CacheType>,
+    pub write_policy: Option<WritePolicy>,
+    pub allocation_type: Option<AllocationType>,
+    pub cache_id: Option<u32>,
+    /// The next level of cache, which may be private to this node or to one of its ancestors.
+    pub next_level: Option<usize>,
+}
+
+/// Identifies the implementation of a processor. This structure has been deprecated since ACPI 6.3.
+#[derive(Clone, Copy, Debug)]
+pub struct ProcessorIdentification {
+    pub vendor_id: [u8; 4],
+    pub level_1_id: u64,
+    pub level_2_id: u64,
+    pub major_revision: u16,
+    pub minor_revision: u16,
+    pub spin_revision: u16,
+}
+
+/// The topology of the processors and caches of the system, as described by the Processor Properties Topology
+/// Table (PPTT).
+#[derive(Clone, Debug)]
+pub struct ProcessorTopology {
+    nodes: Vec<ProcessorNode>,
+    caches: Vec<Cache>,
+}
+
+impl ProcessorTopology {
+    pub fn new<H>(tables: &AcpiTables<H>) -> Result<ProcessorTopology, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let pptt = tables.get_table::<Pptt>()?.ok_or(AcpiError::TableMissing(Signature::PPTT))?;
+        ProcessorTopology::from_pptt(&pptt)
+    }
+
+    fn from_pptt(pptt: &Pptt) -> Result<ProcessorTopology, AcpiError> {
+        /*
+         * Structures refer to each other by their offset from the start of the table, so we first give each one
+         * an index.
+         */
+        let mut node_indices = BTreeMap::new();
+        let mut cache_indices = BTreeMap::new();
+        let mut id_indices = BTreeMap::new();
+        for (offset, entry) in pptt.entries() {
+            match entry {
+                PpttEntry::ProcessorHierarchyNode(..) => node_indices.insert(offset, node_indices.len()),
+                PpttEntry::Cache(..) => cache_indices.insert(offset, cache_indices.len()),
+                PpttEntry::Id(_) => id_indices.insert(offset, id_indices.len()),
+            };
+        }
+
+        let mut nodes = Vec::with_capacity(node_indices.len());
+        let mut caches = Vec::with_capacity(cache_indices.len());
+        let mut ids = Vec::with_capacity(id_indices.len());
+        let mut node_flags = Vec::with_capacity(node_indices.len());
+
+        for (_, entry) in pptt.entries() {
+            match entry {
+                PpttEntry::ProcessorHierarchyNode(entry, private_resources) => {
+                    let flags = entry.flags;
+                    let parent = match entry.parent {
+                        0 => None,
+                        offset => Some(
+                            *node_indices
+                                .get(&offset)
+                                .ok_or(AcpiError::InvalidPptt(PpttError::InvalidReference))?,
+                        ),
+                    };
+
+                    nodes.push(ProcessorNode {
+                        kind: NodeKind::Cluster,
+                        processor_uid: if flags.get_bit(1) { Some(entry.processor_uid) } else { None },
+                        identical_implementation: flags.get_bit(4),
+                        parent,
+                        children: Vec::new(),
+                        private_caches: Vec::new(),
+                        ids: Vec::new(),
+                    });
+                    node_flags.push((flags, entry.private_resources(private_resources)?));
+                }
+
+                PpttEntry::Cache(entry, cache_id) => {
+                    let flags = entry.flags;
+                    let attributes = entry.attributes;
+                    let valid = |bit| flags.get_bit(bit);
+
+                    caches.push(Cache {
+                        level: None,
+                        size: if valid(0) { Some(entry.size) } else { None },
+                        sets: if valid(1) { Some(entry.number_of_sets) } else { None },
+                        associativity: if valid(2) { Some(entry.associativity) } else { None },
+                        allocation_type: if valid(3) {
+                            Some(match attributes.get_bits(0..2) {
+                                0b00 => AllocationType::Read,
+                                0b01 => AllocationType::Write,
+                                _ => AllocationType::ReadWrite,
+                            })
+                        } else {
+                            None
+                        },
+                        cache_type: if valid(4) {
+                            Some(match attributes.get_bits(2..4) {
+                                0b00 => CacheType::Data,
+                                0b01 => CacheType::Instruction,
+                                _ => CacheType::Unified,
+                            })
+                        } else {
+                            None
+                        },
+                        write_policy: if valid(5) {
+                            Some(if attributes.get_bit(4) {
+                                WritePolicy::WriteThrough
+                            } else {
+                                WritePolicy::WriteBack
+                            })
+                        } else {
+                            None
+                        },
+                        line_size: if valid(6) { Some(entry.line_size) } else { None },
+                        /*
+                         * The cache ID was added in revision 3 of the PPTT, and so is only present if the structure
+                         * is long enough to hold it.
+                         */
+                        cache_id: if valid(7) { cache_id } else { None },
+                        next_level: match entry.next_level_of_cache {
+                            0 => None,
+                            offset => Some(
+                                *cache_indices
+                                    .get(&offset)
+                                    .ok_or(AcpiError::InvalidPptt(PpttError::InvalidReference))?,
+                            ),
+                        },
+                    });
+                }
+
+                PpttEntry::Id(entry) => {
+                    ids.push(ProcessorIdentification {
+                        vendor_id: entry.vendor_id,
+                        level_1_id: entry.level_1_id,
+                        level_2_id: entry.level_2_id,
+                        major_revision: entry.major_revision,
+                        minor_revision: entry.minor_revision,
+                        spin_revision: entry.spin_revision,
+                    });
+                }
+            }
+        }
+
+        /*
+         * Check that following the parents of any node reaches the root. A chain that is longer than the number of
+         * nodes must contain a cycle, which would make walking up the hierarchy loop forever.
+         */
+        for index in 0..nodes.len() {
+            let mut ancestor = nodes[index].parent;
+            let mut steps = 0;
+
+            while let Some(node) = ancestor {
+                steps += 1;
+                if steps > nodes.len() {
+                    return Err(AcpiError::InvalidPptt(PpttError::InvalidReference));
+                }
+                ancestor = nodes[node].parent;
+            }
+        }
+
+        /*
+         * Now every structure has been read, we can link the nodes together and resolve their private resources.
+         */
+        for index in 0..nodes.len() {
+            if let Some(parent) = nodes[index].parent {
+                nodes[parent].children.push(index);
+            }
+
+            for &offset in node_flags[index].1.iter() {
+                if let Some(&cache) = cache_indices.get(&offset) {
+                    nodes[index].private_caches.push(cache);
+                } else if let Some(&id) = id_indices.get(&offset) {
+                    nodes[index].ids.push(ids[id]);
+                } else {
+                    return Err(AcpiError::InvalidPptt(PpttError::InvalidReference));
+                }
+            }
+        }
+
+        for index in 0..nodes.len() {
+            let flags = node_flags[index].0;
+            nodes[index].kind = if flags.get_bit(0) {
+                NodeKind::Package
+            } else if flags.get_bit(2) {
+                NodeKind::Thread
+            } else if flags.get_bit(3) || nodes[index].children.iter().any(|&child| node_flags[child].0.get_bit(2))
+            {
+                NodeKind::Core
+            } else {
+                NodeKind::Cluster
+            };
+        }
+
+        /*
+         * Work out the level of each cache by walking up the hierarchy from each leaf node. A node's private
+         * caches, and those that follow them, are the levels after any caches already seen below it.
+         */
+        for leaf in (0..nodes.len()).filter(|&index| nodes[index].children.is_empty()) {
+            let mut seen = Vec::new();
+            let mut max_level = 0u8;
+            let mut ancestor = Some(leaf);
+
+            while let Some(node) = ancestor {
+                let base_level = max_level;
+
+                for &private_cache in nodes[node].private_caches.iter() {
+                    let mut level = base_level;
+                    let mut cache = Some(private_cache);
+
+                    while let Some(index) = cache {
+                        if seen.contains(&index) {
+                            break;
+                        }
+                        seen.push(index);
+
+                        level = level.saturating_add(1);
+                        if caches[index].level < Some(level) {
+                            caches[index].level = Some(level);
+                        }
+                        max_level = max_level.max(level);
+                        cache = caches[index].next_level;
+                    }
+                }
+
+                ancestor = nodes[node].parent;
+            }
+        }
+
+        Ok(ProcessorTopology { nodes, caches })
+    }
+
+    pub fn nodes(&self) -> &[ProcessorNode] {
+        &self.nodes
+    }
+
+    pub fn caches(&self) -> &[Cache] {
+        &self.caches
+    }
+
+    /// Find the leaf node that describes the processor with the given ACPI processor UID.
+    pub fn find_processor(&self, processor_uid: u32) -> Option<usize> {
+        self.nodes.iter().position(|node| node.children.is_empty() && node.processor_uid == Some(processor_uid))
+    }
+
+    /// Find the leaf node that describes a processor from the [`ProcessorInfo`](crate::platform::ProcessorInfo)
+    /// of the system.
+    pub fn node_for(&self, processor: &Processor) -> Option<usize> {
+        self.find_processor(processor.processor_uid)
+    }
+
+    /// Iterate over the given node and all of its ancestors, finishing with the package that contains it.
+    pub fn ancestors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut next = Some(node);
+        core::iter::from_fn(move || {
+            let current = next?;
+            next = self.nodes[current].parent;
+            Some(current)
+        })
+    }
+
+    /// Get all of the caches that the given node uses, including those private to its ancestors. Two nodes share a
+    /// cache if it appears in both of their lists.
+    pub fn caches_of(&self, node: usize) -> Vec<usize> {
+        let mut result: Vec<usize> = Vec::new();
+
+        for ancestor in self.ancestors(node) {
+            for &private_cache in self.nodes[ancestor].private_caches.iter() {
+                let mut cache = Some(private_cache);
+                while let Some(index) = cache {
+                    if result.contains(&index) {
+                        break;
+                    }
+                    result.push(index);
+                    cache = self.caches[index].next_level;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[repr(C, packed)]
+pub(crate) struct Pptt {
+    header: SdtHeader,
+    // Followed by a number of variable-length structures
+}
+
+impl AcpiTable for Pptt {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+unsafe impl CheckedTable for Pptt {
+    const SIGNATURE: Signature = Signature::PPTT;
+}
+
+impl Pptt {
+    /// Iterate over the structures of the PPTT, along with their offsets from the start of the table.
+    fn entries(&self) -> PpttEntryIter<'_> {
+        PpttEntryIter {
+            subtables: SubtableIter::new(unsafe { sdt::table_contents(self) }, SubtableFormat::Short),
+            offset: mem::size_of::<Pptt>() as u32,
+        }
+    }
+}
+
+struct PpttEntryIter<'a> {
+    subtables: SubtableIter<'a>,
+    offset: u32,
+}
+
+enum PpttEntry<'a> {
+    /// A processor hierarchy node, and the list of private resources that follows it.
+    ProcessorHierarchyNode(&'a ProcessorHierarchyNodeEntry, &'a [u8]),
+    /// A cache type structure, and its cache ID if the structure is long enough to have one.
+    Cache(&'a CacheEntry, Option<u32>),
+    Id(&'a IdEntry),
+}
+
+impl<'a> Iterator for PpttEntryIter<'a> {
+    type Item = (u32, PpttEntry<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        /*
+         * Stop at a malformed structure, as there's no way to find the next one.
+         */
+        while let Some(Ok(subtable)) = self.subtables.next() {
+            let offset = self.offset;
+            self.offset += subtable.bytes.len() as u32;
+
+            let entry = match subtable.subtable_type {
+                0 => unsafe { subtable.get::<ProcessorHierarchyNodeEntry>() }.map(|entry| {
+                    PpttEntry::ProcessorHierarchyNode(
+                        entry,
+                        &subtable.bytes[mem::size_of::<ProcessorHierarchyNodeEntry>()..],
+                    )
+                }),
+                /*
+                 * The cache ID field was added to cache type structures in a later revision, so we accept them
+                 * without it.
+                 */
+                1 => unsafe { subtable.get::<CacheEntry>() }.map(|entry| {
+                    let cache_id = subtable
+                        .bytes
+                        .get(mem::size_of::<CacheEntry>()..mem::size_of::<CacheEntry>() + 4)
+                        .map(|id| u32::from_le_bytes([id[0], id[1], id[2], id[3]]));
+                    PpttEntry::Cache(entry, cache_id)
+                }),
+                2 => unsafe { subtable.get::<IdEntry>() }.map(PpttEntry::Id),
+                _ => None,
+            };
+
+            /*
+             * Skip any structures we don't understand, or that are too short to be valid.
+             */
+            if let Some(entry) = entry {
+                return Some((offset, entry));
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct EntryHeader {
+    entry_type: u8,
+    length: u8,
+}
+
+#[repr(C, packed)]
+struct ProcessorHierarchyNodeEntry {
+    header: EntryHeader,
+    _reserved: u16,
+    flags: u32,
+    parent: u32,
+    processor_uid: u32,
+    num_private_resources: u32,
+    // Followed by `num_private_resources` offsets of private resource structures
+}
+
+impl ProcessorHierarchyNodeEntry {
+    /// Read the offsets of the node's private resources from `bytes`, the part of the structure that follows the
+    /// fixed fields.
+    fn private_resources(&self, bytes: &[u8]) -> Result<Vec<u32>, AcpiError> {
+        let count = self.num_private_resources as usize;
+        if count > bytes.len() / 4 {
+            return Err(AcpiError::InvalidPptt(PpttError::InvalidPrivateResourceCount));
+        }
+
+        Ok(bytes[..count * 4]
+            .chunks_exact(4)
+            .map(|offset| u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]))
+            .collect())
+    }
+}
+
+#[repr(C, packed)]
+struct CacheEntry {
+    header: EntryHeader,
+    _reserved: u16,
+    flags: u32,
+    next_level_of_cache: u32,
+    size: u32,
+    number_of_sets: u32,
+    associativity: u8,
+    attributes: u8,
+    line_size: u16,
+    // Followed by the cache ID, from revision 3 of the PPTT
+}
+
+#[repr(C, packed)]
+struct IdEntry {
+    header: EntryHeader,
+    _reserved: u16,
+    vendor_id: [u8; 4],
+    level_1_id: u64,
+    level_2_id: u64,
+    major_revision: u16,
+    minor_revision: u16,
+    spin_revision: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    /// Build a PPTT containing the given structures, which are placed one after another from offset `36`.
+    fn pptt(structures: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = vec![0; mem::size_of::<Pptt>()];
+        bytes[0..4].copy_from_slice(b"PPTT");
+        bytes[8] = 3;
+        for structure in structures {
+            bytes.extend_from_slice(structure);
+        }
+
+        let length = bytes.len() as u32;
+        bytes[4..8].copy_from_slice(&length.to_le_bytes());
+        bytes
+    }
+
+    fn node(flags: u32, parent: u32, processor_uid: u32, private_resources: &[u32]) -> Vec<u8> {
+        let mut node = vec![0x0, (20 + private_resources.len() * 4) as u8, 0, 0];
+        node.extend_from_slice(&flags.to_le_bytes());
+        node.extend_from_slice(&parent.to_le_bytes());
+        node.extend_from_slice(&processor_uid.to_le_bytes());
+        node.extend_from_slice(&(private_resources.len() as u32).to_le_bytes());
+        for resource in private_resources {
+            node.extend_from_slice(&resource.to_le_bytes());
+        }
+        node
+    }
+
+    /// Build a unified cache type structure, which includes a cache ID.
+    fn cache(flags: u32, size: u32, cache_id: u32) -> Vec<u8> {
+        let mut cache = vec![0x1, 28, 0, 0];
+        cache.extend_from_slice(&flags.to_le_bytes());
+        cache.extend_from_slice(&0u32.to_le_bytes());
+        cache.extend_from_slice(&size.to_le_bytes());
+        cache.extend_from_slice(&1024u32.to_le_bytes());
+        cache.extend_from_slice(&[8, 0b1010]);
+        cache.extend_from_slice(&64u16.to_le_bytes());
+        cache.extend_from_slice(&cache_id.to_le_bytes());
+        cache
+    }
+
+    #[test]
+    fn package_with_two_threads() {
+        const L3: u32 = 36;
+        const L2: u32 = 64;
+        const PACKAGE: u32 = 92;
+        const CORE: u32 = 116;
+
+        /*
+         * The L3 has a valid cache ID, while the ID of the L2 is not marked as valid, and so should be ignored.
+         */
+        let bytes = pptt(&[
+            &cache(0xff, 0x80_0000, 3),
+            &cache(0x7f, 0x10_0000, 99),
+            &node(0b11, 0, 100, &[L3]),
+            &node(0b10, PACKAGE, 10, &[L2]),
+            &node(0b1110, CORE, 0, &[]),
+            &node(0b1110, CORE, 1, &[]),
+        ]);
+        let topology = ProcessorTopology::from_pptt(unsafe { &*(bytes.as_ptr() as *const Pptt) }).unwrap();
+
+        let kinds: Vec<NodeKind> = topology.nodes().iter().map(|node| node.kind).collect();
+        assert_eq!(kinds, [NodeKind::Package, NodeKind::Core, NodeKind::Thread, NodeKind::Thread]);
+        assert_eq!(topology.nodes()[1].children, [2, 3]);
+
+        assert_eq!(topology.find_processor(1), Some(3));
+        assert_eq!(topology.find_processor(100), None);
+        assert_eq!(topology.ancestors(3).collect::<Vec<usize>>(), [3, 1, 0]);
+
+        assert_eq!(topology.caches_of(2), [1, 0]);
+        assert_eq!(topology.caches_of(3), topology.caches_of(2));
+        let caches = topology.caches();
+        assert_eq!(caches[0].level, Some(2));
+        assert_eq!(caches[1].level, Some(1));
+        assert_eq!(caches[0].size, Some(0x80_0000));
+        assert_eq!(caches[0].cache_type, Some(CacheType::Unified));
+        assert_eq!(caches[0].cache_id, Some(3));
+        assert_eq!(caches[1].cache_id, None);
+    }
+
+    #[test]
+    fn self_parented_node() {
+        let bytes = pptt(&[&node(0b1110, 36, 0, &[])]);
+        let result = ProcessorTopology::from_pptt(unsafe { &*(bytes.as_ptr() as *const Pptt) });
+        assert!(matches!(result, Err(AcpiError::InvalidPptt(PpttError::InvalidReference))));
+    }
+}
@@ -1,5 +1,5 @@
-use crate::{AcpiError, AcpiHandler};
-use core::{fmt, mem, mem::MaybeUninit, str};
+use crate::{AcpiError, AcpiHandler, AcpiTable, PhysicalMapping};
+use core::{fmt, mem, mem::MaybeUninit, ptr, slice, str};
 
 /// Represents a field which may or may not be present within an ACPI structure, depending on the version of ACPI
 /// that a system supports. If the field is not present, it is not safe to treat the data as initialised.
@@ -125,10 +125,16 @@ impl Signature {
     pub const RSDT: Signature = Signature(*b"RSDT");
     pub const XSDT: Signature = Signature(*b"XSDT");
     pub const FADT: Signature = Signature(*b"FACP");
+    pub const FACS: Signature = Signature(*b"FACS");
     pub const HPET: Signature = Signature(*b"HPET");
     pub const MADT: Signature = Signature(*b"APIC");
     pub const MCFG: Signature = Signature(*b"MCFG");
+    pub const DSDT: Signature = Signature(*b"DSDT");
     pub const SSDT: Signature = Signature(*b"SSDT");
+    pub const SRAT: Signature = Signature(*b"SRAT");
+    pub const SLIT: Signature = Signature(*b"SLIT");
+    pub const PPTT: Signature = Signature(*b"PPTT");
+    pub const HMAT: Signature = Signature(*b"HMAT");
 
     pub fn as_str(&self) -> &str {
         str::from_utf8(&self.0).unwrap()
@@ -157,3 +163,236 @@ where
         unsafe { handler.map_physical_region::<SdtHeader>(physical_address, mem::size_of::<SdtHeader>()) };
     (*mapping).clone()
 }
+
+/// The layout of the header at the start of each variable-length structure within a table.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SubtableFormat {
+    /// A `u8` type followed by a `u8` length. The entries of the MADT and SRAT, and the structures of the PPTT,
+    /// have this header.
+    Short,
+    /// A `u16` type, two reserved bytes, and a `u32` length. The structures of the HMAT have this header.
+    Long,
+}
+
+impl SubtableFormat {
+    fn header_length(self) -> usize {
+        match self {
+            SubtableFormat::Short => 2,
+            SubtableFormat::Long => 8,
+        }
+    }
+}
+
+/// One of the variable-length structures that follow the fixed part of a table, such as an entry of the MADT.
+/// `bytes` covers the whole structure, including its header.
+#[derive(Clone, Copy, Debug)]
+pub struct Subtable<'a> {
+    pub subtable_type: u16,
+    pub bytes: &'a [u8],
+}
+
+impl<'a> Subtable<'a> {
+    /// Read the structure as a `T`. Returns `None` if it is too short to contain a `T`.
+    ///
+    /// ### Safety
+    /// Any bit pattern must be a valid `T`.
+    pub unsafe fn read<T: Copy>(&self) -> Option<T> {
+        if self.bytes.len() < mem::size_of::<T>() {
+            return None;
+        }
+
+        Some(unsafe { ptr::read_unaligned(self.bytes.as_ptr() as *const T) })
+    }
+
+    /// Get a reference to the structure as a `T`, without copying it. Returns `None` if it is too short to
+    /// contain a `T`.
+    ///
+    /// ### Safety
+    /// Any bit pattern must be a valid `T`, and `T` must have an alignment of `1` (i.e. be `#[repr(C, packed)]`).
+    pub unsafe fn get<T>(&self) -> Option<&'a T> {
+        debug_assert_eq!(mem::align_of::<T>(), 1);
+        if self.bytes.len() < mem::size_of::<T>() {
+            return None;
+        }
+
+        Some(unsafe { &*(self.bytes.as_ptr() as *const T) })
+    }
+}
+
+/// Iterates over the variable-length structures of a table, checking that each of them fits within the table. If
+/// a structure has an invalid length, `AcpiError::InvalidSubtableLength` is returned, and iteration stops.
+pub struct SubtableIter<'a> {
+    bytes: &'a [u8],
+    format: SubtableFormat,
+}
+
+impl<'a> SubtableIter<'a> {
+    pub(crate) fn new(bytes: &'a [u8], format: SubtableFormat) -> SubtableIter<'a> {
+        SubtableIter { bytes, format }
+    }
+}
+
+impl<'a> Iterator for SubtableIter<'a> {
+    type Item = Result<Subtable<'a>, AcpiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let header_length = self.format.header_length();
+        if self.bytes.len() < header_length {
+            self.bytes = &[];
+            return Some(Err(AcpiError::InvalidSubtableLength));
+        }
+
+        let (subtable_type, length) = match self.format {
+            SubtableFormat::Short => (self.bytes[0] as u16, self.bytes[1] as usize),
+            SubtableFormat::Long => (
+                u16::from_le_bytes([self.bytes[0], self.bytes[1]]),
+                u32::from_le_bytes([self.bytes[4], self.bytes[5], self.bytes[6], self.bytes[7]]) as usize,
+            ),
+        };
+
+        if length < header_length || length > self.bytes.len() {
+            self.bytes = &[];
+            return Some(Err(AcpiError::InvalidSubtableLength));
+        }
+
+        let (subtable, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Some(Ok(Subtable { subtable_type, bytes: subtable }))
+    }
+}
+
+/// Iterate over the variable-length structures that follow the fixed part of a table (the first
+/// `size_of::<T>()` bytes). The structures are bounded by both the length of the table and the length of the
+/// mapping, so a table that claims to be longer than it was mapped as can't be read past the end of the mapping.
+pub fn subtables<H, T>(mapping: &PhysicalMapping<H, T>, format: SubtableFormat) -> SubtableIter<'_>
+where
+    H: AcpiHandler,
+    T: AcpiTable,
+{
+    let length = usize::min(mapping.header().length as usize, mapping.region_length);
+    let bytes = if length > mem::size_of::<T>() {
+        unsafe {
+            slice::from_raw_parts(
+                (mapping.virtual_start.as_ptr() as *const u8).add(mem::size_of::<T>()),
+                length - mem::size_of::<T>(),
+            )
+        }
+    } else {
+        &[]
+    };
+
+    SubtableIter::new(bytes, format)
+}
+
+/// Get the bytes of a table that follow its fixed part (the first `size_of::<T>()` bytes), as given by the length
+/// in its header. This is empty if the table is too short to have any.
+///
+/// ### Safety
+/// The table must be mapped for the whole of the length in its header, as tables returned by
+/// [`AcpiTables`](crate::AcpiTables) are.
+pub(crate) unsafe fn table_contents<T: AcpiTable>(table: &T) -> &[u8] {
+    let length = table.header().length as usize;
+    if length <= mem::size_of::<T>() {
+        return &[];
+    }
+
+    unsafe {
+        slice::from_raw_parts(
+            (table as *const T as *const u8).add(mem::size_of::<T>()),
+            length - mem::size_of::<T>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[derive(Clone, Copy)]
+    #[repr(C, packed)]
+    struct TestEntry {
+        entry_type: u8,
+        length: u8,
+        value: u32,
+    }
+
+    fn assert_invalid_length(mut iter: SubtableIter) {
+        assert!(matches!(iter.next(), Some(Err(AcpiError::InvalidSubtableLength))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn short_format() {
+        let bytes = [0x1, 3, 0xaa, 0x80, 2, 0x02, 6, 0x78, 0x56, 0x34, 0x12];
+        let subtables: Vec<Subtable> =
+            SubtableIter::new(&bytes, SubtableFormat::Short).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(subtables.len(), 3);
+        assert_eq!(subtables[0].subtable_type, 0x1);
+        assert_eq!(subtables[0].bytes, [0x1, 3, 0xaa]);
+        assert_eq!(subtables[1].subtable_type, 0x80);
+        assert_eq!(subtables[1].bytes, [0x80, 2]);
+        assert_eq!(subtables[2].bytes.len(), 6);
+        assert_eq!(unsafe { subtables[2].read::<TestEntry>() }.map(|entry| entry.value), Some(0x1234_5678));
+        assert_eq!(unsafe { subtables[2].get::<TestEntry>() }.map(|entry| entry.value), Some(0x1234_5678));
+    }
+
+    #[test]
+    fn long_format() {
+        let bytes = [0x01, 0x02, 0xff, 0xff, 10, 0, 0, 0, 0xaa, 0xbb, 0x03, 0x00, 0, 0, 8, 0, 0, 0];
+        let subtables: Vec<Subtable> =
+            SubtableIter::new(&bytes, SubtableFormat::Long).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(subtables.len(), 2);
+        assert_eq!(subtables[0].subtable_type, 0x0201);
+        assert_eq!(subtables[0].bytes.len(), 10);
+        assert_eq!(subtables[1].subtable_type, 0x3);
+        assert_eq!(subtables[1].bytes.len(), 8);
+
+        /*
+         * A length that only fits in the upper bytes of the `u32` must not be truncated.
+         */
+        let bytes = [0x01, 0x00, 0, 0, 8, 0, 0, 1];
+        assert_invalid_length(SubtableIter::new(&bytes, SubtableFormat::Long));
+        assert_invalid_length(SubtableIter::new(&bytes[0..6], SubtableFormat::Long));
+    }
+
+    #[test]
+    fn zero_length() {
+        assert_invalid_length(SubtableIter::new(&[0x1, 0, 0x2, 2], SubtableFormat::Short));
+        assert_invalid_length(SubtableIter::new(&[0x1, 0, 0, 0, 0, 0, 0, 0], SubtableFormat::Long));
+    }
+
+    #[test]
+    fn length_shorter_than_header() {
+        assert_invalid_length(SubtableIter::new(&[0x1, 1, 0x2, 2], SubtableFormat::Short));
+        assert_invalid_length(SubtableIter::new(&[0x1, 0, 0, 0, 7, 0, 0, 0], SubtableFormat::Long));
+    }
+
+    #[test]
+    fn length_past_end() {
+        let mut iter = SubtableIter::new(&[0x1, 2, 0x2, 4, 0], SubtableFormat::Short);
+        assert_eq!(iter.next().unwrap().unwrap().subtable_type, 0x1);
+        assert_invalid_length(iter);
+
+        /*
+         * A trailing byte that can't even hold a header is also an error.
+         */
+        let mut iter = SubtableIter::new(&[0x1, 2, 0x2], SubtableFormat::Short);
+        assert!(iter.next().unwrap().is_ok());
+        assert_invalid_length(iter);
+    }
+
+    #[test]
+    fn subtable_shorter_than_type() {
+        let bytes = [0x1, 5, 0x78, 0x56, 0x34];
+        let subtable = SubtableIter::new(&bytes, SubtableFormat::Short).next().unwrap().unwrap();
+        assert!(unsafe { subtable.get::<TestEntry>() }.is_none());
+        assert!(unsafe { subtable.read::<TestEntry>() }.is_none());
+    }
+}
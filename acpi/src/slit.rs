@@ -0,0 +1,101 @@
+use crate::{
+    sdt::{self, SdtHeader, Signature},
+    AcpiError,
+    AcpiHandler,
+    AcpiTable,
+    AcpiTables,
+    CheckedTable,
+};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+#[derive(Debug)]
+pub enum SlitError {
+    /// The distance matrix described by the locality count does not fit in the table.
+    MatrixExceedsTable,
+    /// The distance from a locality to itself must always be `10`.
+    InvalidSelfDistance,
+}
+
+/// Represents the System Locality Information Table (SLIT). This contains a matrix of the relative distances
+/// between each pair of system localities (proximity domains), where the distance from a locality to itself is
+/// normalized to `10`.
+#[repr(C, packed)]
+pub struct Slit {
+    header: SdtHeader,
+    locality_count: u64,
+    // Followed by `locality_count * locality_count` bytes of distances
+}
+
+impl AcpiTable for Slit {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+unsafe impl CheckedTable for Slit {
+    const SIGNATURE: Signature = Signature::SLIT;
+}
+
+impl Slit {
+    pub fn locality_count(&self) -> u64 {
+        self.locality_count
+    }
+
+    /// Get the distance matrix, in row-major order. The entry at `[from * locality_count + to]` is the distance
+    /// from locality `from` to locality `to`. This checks that the matrix fits within the table.
+    pub fn matrix(&self) -> Result<&[u8], AcpiError> {
+        let contents = unsafe { sdt::table_contents(self) };
+        let length = usize::try_from(self.locality_count)
+            .ok()
+            .and_then(|count| count.checked_mul(count))
+            .filter(|&length| length <= contents.len())
+            .ok_or(AcpiError::InvalidSlit(SlitError::MatrixExceedsTable))?;
+
+        Ok(&contents[..length])
+    }
+}
+
+/// Describes the relative distances between the system localities (proximity domains) of the system, as given by
+/// the SLIT. Locality numbers are the same proximity domains used by the SRAT.
+#[derive(Clone, Debug)]
+pub struct NumaDistances {
+    locality_count: usize,
+    distances: Vec<u8>,
+}
+
+impl NumaDistances {
+    /// A distance of this value means that the destination locality is unreachable from the source locality.
+    pub const UNREACHABLE: u8 = 0xff;
+
+    pub fn new<H>(tables: &AcpiTables<H>) -> Result<NumaDistances, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let slit = tables.get_table::<Slit>()?.ok_or(AcpiError::TableMissing(Signature::SLIT))?;
+
+        let matrix = slit.matrix()?;
+        let locality_count = slit.locality_count() as usize;
+
+        if (0..locality_count).any(|i| matrix[i * locality_count + i] != 10) {
+            return Err(AcpiError::InvalidSlit(SlitError::InvalidSelfDistance));
+        }
+
+        Ok(NumaDistances { locality_count, distances: matrix.to_vec() })
+    }
+
+    pub fn locality_count(&self) -> usize {
+        self.locality_count
+    }
+
+    /// Get the relative distance from locality `from` to locality `to`, or `None` if either locality does not
+    /// exist. A distance of [`NumaDistances::UNREACHABLE`] means there is no path between the two.
+    pub fn distance(&self, from: u32, to: u32) -> Option<u8> {
+        let (from, to) = (from as usize, to as usize);
+        if from >= self.locality_count || to >= self.locality_count {
+            return None;
+        }
+
+        Some(self.distances[from * self.locality_count + to])
+    }
+}
@@ -0,0 +1,382 @@
+use crate::{
+    sdt::{self, SdtHeader, Signature, SubtableFormat, SubtableIter},
+    AcpiError,
+    AcpiHandler,
+    AcpiTable,
+    AcpiTables,
+    CheckedTable,
+};
+use alloc::vec::Vec;
+use bit_field::BitField;
+
+/// Describes a range of physical memory, and the proximity domain (NUMA node) it belongs to.
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryAffinity {
+    pub base_address: u64,
+    pub length: u64,
+    pub proximity_domain: u32,
+    /// If this is set, the memory range may be hot-plugged or hot-removed.
+    pub hot_pluggable: bool,
+    /// If this is set, the memory range is non-volatile (e.g. NVDIMMs).
+    pub non_volatile: bool,
+}
+
+/// Identifies the processor described by a `ProcessorAffinity`. This should be matched against the processors
+/// described by the MADT.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AffinityProcessor {
+    /// A processor with a local APIC or SAPIC. `local_sapic_eid` is only meaningful on SAPIC systems.
+    LocalApic {
+        apic_id: u8,
+        local_sapic_eid: u8,
+    },
+    X2Apic {
+        x2apic_id: u32,
+    },
+    /// A processor with a GIC CPU interface, identified by its ACPI processor UID.
+    Gicc {
+        processor_uid: u32,
+    },
+}
+
+/// Describes the proximity domain (NUMA node) a processor belongs to.
+#[derive(Clone, Copy, Debug)]
+pub struct ProcessorAffinity {
+    pub processor: AffinityProcessor,
+    pub proximity_domain: u32,
+    /// Processors in the same clock domain share a common clock source.
+    pub clock_domain: u32,
+}
+
+/// Describes the proximity domain a GIC Interrupt Translation Service belongs to. `its_id` matches the ID of an
+/// ITS described by the MADT.
+#[derive(Clone, Copy, Debug)]
+pub struct GicItsAffinity {
+    pub its_id: u32,
+    pub proximity_domain: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeviceHandle {
+    /// Refers to a device in the namespace by its `_HID` and `_UID` objects.
+    Acpi {
+        hid: [u8; 8],
+        uid: u32,
+    },
+    Pci {
+        segment: u16,
+        bus: u8,
+        device: u8,
+        function: u8,
+    },
+    Reserved(u8),
+}
+
+/// Describes the proximity domain of a Generic Initiator - a device, such as an accelerator, that can initiate
+/// memory transactions but is not a processor.
+#[derive(Clone, Copy, Debug)]
+pub struct GenericInitiatorAffinity {
+    pub device_handle: DeviceHandle,
+    pub proximity_domain: u32,
+    /// If this is set, the initiator supports the full set of architectural transactions of a processor (e.g.
+    /// cache-coherent accesses).
+    pub architectural_transactions: bool,
+}
+
+/// Information about the NUMA topology of the system, parsed from the System Resource Affinity Table (SRAT).
+/// Entries that the SRAT marks as disabled are not included.
+#[derive(Clone, Debug)]
+pub struct SratInfo {
+    pub memory_affinity: Vec<MemoryAffinity>,
+    pub processor_affinity: Vec<ProcessorAffinity>,
+    pub gic_its_affinity: Vec<GicItsAffinity>,
+    pub generic_initiator_affinity: Vec<GenericInitiatorAffinity>,
+}
+
+impl SratInfo {
+    pub fn new<H>(tables: &AcpiTables<H>) -> Result<SratInfo, AcpiError>
+    where
+        H: AcpiHandler,
+    {
+        let srat = tables.get_table::<Srat>()?.ok_or(AcpiError::TableMissing(Signature::SRAT))?;
+        let revision = srat.header.revision;
+
+        let mut memory_affinity = Vec::new();
+        let mut processor_affinity = Vec::new();
+        let mut gic_its_affinity = Vec::new();
+        let mut generic_initiator_affinity = Vec::new();
+
+        for entry in srat.entries() {
+            match entry {
+                SratEntry::LocalApic(entry) => {
+                    if !{ entry.flags }.get_bit(0) {
+                        continue;
+                    }
+
+                    /*
+                     * Only the low 8 bits of the proximity domain are used in revision 1 of the SRAT. The high
+                     * bits were added in ACPI 3.0.
+                     */
+                    let mut proximity_domain = entry.proximity_domain_low as u32;
+                    if revision >= 2 {
+                        let [b1, b2, b3] = entry.proximity_domain_high;
+                        proximity_domain |= u32::from_le_bytes([0, b1, b2, b3]);
+                    }
+
+                    processor_affinity.push(ProcessorAffinity {
+                        processor: AffinityProcessor::LocalApic {
+                            apic_id: entry.apic_id,
+                            local_sapic_eid: entry.local_sapic_eid,
+                        },
+                        proximity_domain,
+                        clock_domain: entry.clock_domain,
+                    });
+                }
+
+                SratEntry::Memory(entry) => {
+                    let flags = entry.flags;
+                    if !flags.get_bit(0) {
+                        continue;
+                    }
+
+                    /*
+                     * As with local APIC entries, only the low 8 bits of the proximity domain are used in revision
+                     * 1 of the SRAT.
+                     */
+                    let mut proximity_domain = entry.proximity_domain;
+                    if revision < 2 {
+                        proximity_domain &= 0xff;
+                    }
+
+                    memory_affinity.push(MemoryAffinity {
+                        base_address: (entry.base_address_low as u64) | ((entry.base_address_high as u64) << 32),
+                        length: (entry.length_low as u64) | ((entry.length_high as u64) << 32),
+                        proximity_domain,
+                        hot_pluggable: flags.get_bit(1),
+                        non_volatile: flags.get_bit(2),
+                    });
+                }
+
+                SratEntry::X2Apic(entry) => {
+                    if !{ entry.flags }.get_bit(0) {
+                        continue;
+                    }
+
+                    processor_affinity.push(ProcessorAffinity {
+                        processor: AffinityProcessor::X2Apic { x2apic_id: entry.x2apic_id },
+                        proximity_domain: entry.proximity_domain,
+                        clock_domain: entry.clock_domain,
+                    });
+                }
+
+                SratEntry::Gicc(entry) => {
+                    if !{ entry.flags }.get_bit(0) {
+                        continue;
+                    }
+
+                    processor_affinity.push(ProcessorAffinity {
+                        processor: AffinityProcessor::Gicc { processor_uid: entry.processor_uid },
+                        proximity_domain: entry.proximity_domain,
+                        clock_domain: entry.clock_domain,
+                    });
+                }
+
+                SratEntry::GicIts(entry) => {
+                    gic_its_affinity
+                        .push(GicItsAffinity { its_id: entry.its_id, proximity_domain: entry.proximity_domain });
+                }
+
+                SratEntry::GenericInitiator(entry) => {
+                    let flags = entry.flags;
+                    if !flags.get_bit(0) {
+                        continue;
+                    }
+
+                    let handle = entry.device_handle;
+                    let device_handle = match entry.device_handle_type {
+                        0 => DeviceHandle::Acpi {
+                            hid: [
+                                handle[0], handle[1], handle[2], handle[3], handle[4], handle[5], handle[6],
+                                handle[7],
+                            ],
+                            uid: u32::from_le_bytes([handle[8], handle[9], handle[10], handle[11]]),
+                        },
+                        1 => DeviceHandle::Pci {
+                            segment: u16::from_le_bytes([handle[0], handle[1]]),
+                            bus: handle[2],
+                            device: handle[3].get_bits(3..8),
+                            function: handle[3].get_bits(0..3),
+                        },
+                        other => DeviceHandle::Reserved(other),
+                    };
+
+                    generic_initiator_affinity.push(GenericInitiatorAffinity {
+                        device_handle,
+                        proximity_domain: entry.proximity_domain,
+                        architectural_transactions: flags.get_bit(1),
+                    });
+                }
+            }
+        }
+
+        Ok(SratInfo { memory_affinity, processor_affinity, gic_its_affinity, generic_initiator_affinity })
+    }
+
+    /// Get the proximity domain of the memory at the given physical address, if it's described by the SRAT.
+    pub fn proximity_domain_of_address(&self, address: u64) -> Option<u32> {
+        self.memory_affinity
+            .iter()
+            .find(|range| address >= range.base_address && address - range.base_address < range.length)
+            .map(|range| range.proximity_domain)
+    }
+}
+
+#[repr(C, packed)]
+pub(crate) struct Srat {
+    header: SdtHeader,
+    _reserved1: u32,
+    _reserved2: u64,
+    // Followed by a number of variable-length affinity structures
+}
+
+impl AcpiTable for Srat {
+    fn header(&self) -> &SdtHeader {
+        &self.header
+    }
+}
+
+unsafe impl CheckedTable for Srat {
+    const SIGNATURE: Signature = Signature::SRAT;
+}
+
+impl Srat {
+    fn entries(&self) -> SratEntryIter<'_> {
+        SratEntryIter { subtables: SubtableIter::new(unsafe { sdt::table_contents(self) }, SubtableFormat::Short) }
+    }
+}
+
+struct SratEntryIter<'a> {
+    subtables: SubtableIter<'a>,
+}
+
+enum SratEntry<'a> {
+    LocalApic(&'a LocalApicAffinityEntry),
+    Memory(&'a MemoryAffinityEntry),
+    X2Apic(&'a X2ApicAffinityEntry),
+    Gicc(&'a GiccAffinityEntry),
+    GicIts(&'a GicItsAffinityEntry),
+    GenericInitiator(&'a GenericInitiatorAffinityEntry),
+}
+
+impl<'a> Iterator for SratEntryIter<'a> {
+    type Item = SratEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        /*
+         * Stop at a malformed entry, as there's no way to find the next one.
+         */
+        while let Some(Ok(subtable)) = self.subtables.next() {
+            macro_rules! construct_entry {
+                ($(($value:expr => $variant:path as $type:ty)),*) => {
+                    match subtable.subtable_type {
+                        $(
+                            $value => {
+                                if let Some(entry) = unsafe { subtable.get::<$type>() } {
+                                    return Some($variant(entry));
+                                }
+                            }
+                        )*
+
+                        /*
+                         * Skip any entries we don't understand, or that are too short to be valid.
+                         */
+                        _ => {}
+                    }
+                }
+            }
+
+            #[rustfmt::skip]
+            construct_entry!(
+                (0x0 => SratEntry::LocalApic as LocalApicAffinityEntry),
+                (0x1 => SratEntry::Memory as MemoryAffinityEntry),
+                (0x2 => SratEntry::X2Apic as X2ApicAffinityEntry),
+                (0x3 => SratEntry::Gicc as GiccAffinityEntry),
+                (0x4 => SratEntry::GicIts as GicItsAffinityEntry),
+                (0x5 => SratEntry::GenericInitiator as GenericInitiatorAffinityEntry)
+            );
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+struct EntryHeader {
+    entry_type: u8,
+    length: u8,
+}
+
+#[repr(C, packed)]
+struct LocalApicAffinityEntry {
+    header: EntryHeader,
+    proximity_domain_low: u8,
+    apic_id: u8,
+    flags: u32,
+    local_sapic_eid: u8,
+    proximity_domain_high: [u8; 3],
+    clock_domain: u32,
+}
+
+#[repr(C, packed)]
+struct MemoryAffinityEntry {
+    header: EntryHeader,
+    proximity_domain: u32,
+    _reserved1: u16,
+    base_address_low: u32,
+    base_address_high: u32,
+    length_low: u32,
+    length_high: u32,
+    _reserved2: u32,
+    flags: u32,
+    _reserved3: u64,
+}
+
+#[repr(C, packed)]
+struct X2ApicAffinityEntry {
+    header: EntryHeader,
+    _reserved1: u16,
+    proximity_domain: u32,
+    x2apic_id: u32,
+    flags: u32,
+    clock_domain: u32,
+    _reserved2: u32,
+}
+
+#[repr(C, packed)]
+struct GiccAffinityEntry {
+    header: EntryHeader,
+    proximity_domain: u32,
+    processor_uid: u32,
+    flags: u32,
+    clock_domain: u32,
+}
+
+#[repr(C, packed)]
+struct GicItsAffinityEntry {
+    header: EntryHeader,
+    proximity_domain: u32,
+    _reserved: u16,
+    its_id: u32,
+}
+
+#[repr(C, packed)]
+struct GenericInitiatorAffinityEntry {
+    header: EntryHeader,
+    _reserved1: u8,
+    device_handle_type: u8,
+    proximity_domain: u32,
+    device_handle: [u8; 16],
+    flags: u32,
+    _reserved2: u32,
+}
@@ -0,0 +1,194 @@
+//! Firmware frequently contains tables that are not quite correct. By default, a table that fails validation can't
+//! be used, but this can be relaxed with a [`ValidationPolicy`]. Separately, a [`ValidationReport`] can be
+//! created to find every problem with the tables, so they can be logged.
+
+use crate::{
+    fadt::Fadt,
+    hmat::Hmat,
+    hpet::HpetTable,
+    madt::Madt,
+    mcfg::Mcfg,
+    pptt::Pptt,
+    sdt::{self, SdtHeader, Signature},
+    slit::Slit,
+    srat::Srat,
+    AcpiHandler,
+    AcpiTables,
+    CheckedTable,
+};
+use alloc::vec::Vec;
+use core::{mem, ops::RangeInclusive, slice};
+
+/// Controls what happens when a table fails validation as it is accessed (for example, if its checksum is
+/// incorrect).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValidationPolicy {
+    /// The table can't be used, and accessing it returns an error.
+    Strict,
+    /// The table is used anyway, and a warning is logged.
+    Warn,
+    /// The table is used anyway, without logging anything.
+    Ignore,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValidationProblem {
+    InvalidChecksum {
+        signature: Signature,
+        physical_address: usize,
+    },
+    /// The table has a revision that this library doesn't expect. This is only checked for tables that the
+    /// library can parse.
+    UnexpectedRevision {
+        signature: Signature,
+        physical_address: usize,
+        revision: u8,
+    },
+    /// The table is shorter than the structure that represents it (for the revision of the table it claims to
+    /// be).
+    TooShort {
+        signature: Signature,
+        physical_address: usize,
+        length: u32,
+        expected_length: usize,
+    },
+    /// The FADT's 32-bit and 64-bit DSDT addresses are both non-zero, but point to different places.
+    DsdtAddressMismatch {
+        dsdt_address: u32,
+        x_dsdt_address: u64,
+    },
+    /// The FADT's 32-bit and 64-bit FACS addresses are both non-zero, but point to different places.
+    FacsAddressMismatch {
+        firmware_ctrl: u32,
+        x_firmware_ctrl: u64,
+    },
+    /// Two tables occupy some of the same memory.
+    OverlappingTables {
+        first: Signature,
+        first_address: usize,
+        second: Signature,
+        second_address: usize,
+    },
+}
+
+/// A list of every problem found in the tables. This is created by
+/// [`AcpiTables::validation_report`](crate::AcpiTables::validation_report).
+#[derive(Clone, Debug)]
+pub struct ValidationReport {
+    pub problems: Vec<ValidationProblem>,
+}
+
+impl ValidationReport {
+    pub(crate) fn new<H>(tables: &AcpiTables<H>) -> ValidationReport
+    where
+        H: AcpiHandler,
+    {
+        let mut problems = Vec::new();
+
+        /*
+         * The DSDT isn't listed by the RSDT/XSDT, so we need to check it separately.
+         */
+        let dsdt_address = tables.dsdt.as_ref().map(|dsdt| dsdt.address - mem::size_of::<SdtHeader>());
+        let mut to_check: Vec<(usize, SdtHeader)> =
+            tables.all_tables.iter().map(|table| (table.physical_address, table.header)).collect();
+        if let Some(dsdt_address) = dsdt_address {
+            to_check.push((dsdt_address, sdt::peek_at_sdt_header(&tables.handler, dsdt_address)));
+        }
+
+        for &(physical_address, header) in to_check.iter() {
+            check_table(&tables.handler, physical_address, &header, &mut problems);
+        }
+
+        /*
+         * Look for overlapping tables by sorting them by address, and checking that each table ends before the
+         * next one starts.
+         */
+        to_check.sort_by_key(|&(physical_address, _)| physical_address);
+        for pair in to_check.windows(2) {
+            let (first_address, first) = pair[0];
+            let (second_address, second) = pair[1];
+
+            if second_address < first_address + first.length as usize {
+                problems.push(ValidationProblem::OverlappingTables {
+                    first: first.signature,
+                    first_address,
+                    second: second.signature,
+                    second_address,
+                });
+            }
+        }
+
+        ValidationReport { problems }
+    }
+
+    /// Returns `true` if no problems were found.
+    pub fn is_clean(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+fn check_table<H>(handler: &H, physical_address: usize, header: &SdtHeader, problems: &mut Vec<ValidationProblem>)
+where
+    H: AcpiHandler,
+{
+    let signature = header.signature;
+    let length = header.length;
+    let revision = header.revision;
+
+    if (length as usize) < mem::size_of::<SdtHeader>() {
+        problems.push(ValidationProblem::TooShort {
+            signature,
+            physical_address,
+            length,
+            expected_length: mem::size_of::<SdtHeader>(),
+        });
+        return;
+    }
+
+    let mapping = unsafe { handler.map_physical_region::<u8>(physical_address, length as usize) };
+    let bytes = unsafe { slice::from_raw_parts(mapping.virtual_start.as_ptr() as *const u8, length as usize) };
+    if bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)) != 0 {
+        problems.push(ValidationProblem::InvalidChecksum { signature, physical_address });
+    }
+
+    /*
+     * The revisions of each table up to those defined by ACPI 6.5.
+     */
+    let (expected_revisions, expected_length): (RangeInclusive<u8>, usize) = match signature {
+        Signature::FADT => (1..=6, Fadt::min_length(revision)),
+        Signature::MADT => (1..=6, Madt::min_length(revision)),
+        Signature::HPET => (1..=1, HpetTable::min_length(revision)),
+        Signature::MCFG => (1..=1, Mcfg::min_length(revision)),
+        Signature::SRAT => (1..=3, Srat::min_length(revision)),
+        Signature::SLIT => (1..=1, Slit::min_length(revision)),
+        Signature::PPTT => (1..=3, Pptt::min_length(revision)),
+        Signature::HMAT => (1..=2, Hmat::min_length(revision)),
+        Signature::DSDT | Signature::SSDT => (1..=2, mem::size_of::<SdtHeader>()),
+        _ => return,
+    };
+
+    if !expected_revisions.contains(&revision) {
+        problems.push(ValidationProblem::UnexpectedRevision { signature, physical_address, revision });
+    }
+
+    if (length as usize) < expected_length {
+        problems.push(ValidationProblem::TooShort { signature, physical_address, length, expected_length });
+        return;
+    }
+
+    if signature == Signature::FADT {
+        let fadt = unsafe { handler.map_physical_region::<Fadt>(physical_address, length as usize) };
+
+        if let (dsdt_address, Some(x_dsdt_address)) = fadt.raw_dsdt_addresses() {
+            if dsdt_address != 0 && x_dsdt_address != 0 && dsdt_address as u64 != x_dsdt_address {
+                problems.push(ValidationProblem::DsdtAddressMismatch { dsdt_address, x_dsdt_address });
+            }
+        }
+
+        if let (firmware_ctrl, Some(x_firmware_ctrl)) = fadt.raw_facs_addresses() {
+            if firmware_ctrl != 0 && x_firmware_ctrl != 0 && firmware_ctrl as u64 != x_firmware_ctrl {
+                problems.push(ValidationProblem::FacsAddressMismatch { firmware_ctrl, x_firmware_ctrl });
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //! This crate provides types for representing the RSDP (the Root System Descriptor Table; the first ACPI table)
-//! and methods for searching for it on BIOS systems. Importantly, this crate (unlike `acpi`, which re-exports the
-//! contents of this crate) does not need `alloc`, and so can be used in environments that can't allocate. This is
-//! specifically meant to be used from bootloaders for finding the RSDP, so it can be passed to the payload.
+//! and methods for searching for it on BIOS and UEFI systems. Importantly, this crate (unlike `acpi`, which
+//! re-exports the contents of this crate) does not need `alloc`, and so can be used in environments that can't
+//! allocate. This is specifically meant to be used from bootloaders for finding the RSDP, so it can be passed to
+//! the payload.
 //!
 //! To use this crate, you will need to provide an implementation of `AcpiHandler`. This is the same handler type
 //! used in the `acpi` crate.
@@ -11,10 +12,12 @@
 #![deny(unsafe_op_in_unsafe_fn)]
 
 pub mod handler;
+pub mod uefi;
 
 use core::{mem, ops::Range, slice, str};
 use handler::{AcpiHandler, PhysicalMapping};
 use log::warn;
+use uefi::{ConfigurationTableEntry, ACPI2_GUID, ACPI_GUID};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum RsdpError {
@@ -22,6 +25,8 @@ pub enum RsdpError {
     IncorrectSignature,
     InvalidOemId,
     InvalidChecksum,
+    /// The structure passed by the bootloader, such as the Multiboot2 information structure, was malformed.
+    InvalidBootInfo,
 }
 
 /// The first structure found in ACPI. It just tells us where the RSDT is.
@@ -70,6 +75,8 @@ impl Rsdp {
     /// side-effects. On UEFI systems, the RSDP should be found in the Configuration Table, using two GUIDs:
     ///     - ACPI v1.0 structures use `eb9d2d30-2d88-11d3-9a16-0090273fc14d`.
     ///     - ACPI v2.0 or later structures use `8868e871-e4f1-11d3-bc22-0080c73c8881`.
+    ///
+    /// [`Rsdp::search_for_on_uefi`] can be used to do this.
     pub unsafe fn search_for_on_bios<H>(handler: H) -> Result<PhysicalMapping<H, Rsdp>, RsdpError>
     where
         H: AcpiHandler,
@@ -110,13 +117,167 @@ impl Rsdp {
         }
     }
 
+    /// This searches for a RSDP in the UEFI Configuration Table, which can be found in the `EFI_SYSTEM_TABLE`.
+    /// Entries with the ACPI 2.0 GUID are preferred, and entries with the ACPI 1.0 GUID are only used if there
+    /// isn't a valid ACPI 2.0 RSDP. Each RSDP found is validated, and invalid ones are skipped.
+    ///
+    /// ### Safety
+    /// `config_table` must be the Configuration Table provided by the firmware, so that the addresses of the
+    /// tables it describes can be mapped.
+    pub unsafe fn search_for_on_uefi<H>(
+        handler: H,
+        config_table: &[ConfigurationTableEntry],
+    ) -> Result<PhysicalMapping<H, Rsdp>, RsdpError>
+    where
+        H: AcpiHandler,
+    {
+        let acpi2_entries = config_table.iter().filter(|entry| entry.vendor_guid == ACPI2_GUID);
+        let acpi_entries = config_table.iter().filter(|entry| entry.vendor_guid == ACPI_GUID);
+
+        for entry in acpi2_entries.chain(acpi_entries) {
+            let rsdp_mapping =
+                unsafe { handler.map_physical_region::<Rsdp>(entry.vendor_table, mem::size_of::<Rsdp>()) };
+
+            match rsdp_mapping.validate() {
+                Ok(()) => return Ok(rsdp_mapping),
+                Err(err) => warn!("Invalid RSDP found at {:#x}: {:?}", entry.vendor_table, err),
+            }
+        }
+
+        Err(RsdpError::NoValidRsdp)
+    }
+
+    /// This finds the RSDP in the Multiboot2 boot information structure at `info_address`. Multiboot2
+    /// bootloaders (such as GRUB) provide a copy of the RSDP in either an "ACPI new RSDP" tag (for ACPI 2.0 and
+    /// later), or an "ACPI old RSDP" tag (for ACPI 1.0). The new RSDP is preferred if both are present. The RSDP
+    /// is validated, and a mapping of the copy inside the boot information structure is returned.
+    ///
+    /// ### Safety
+    /// `info_address` must be the physical address of a Multiboot2 boot information structure.
+    pub unsafe fn search_for_on_multiboot2<H>(
+        handler: H,
+        info_address: usize,
+    ) -> Result<PhysicalMapping<H, Rsdp>, RsdpError>
+    where
+        H: AcpiHandler,
+    {
+        const TAG_TYPE_END: u32 = 0;
+        const TAG_TYPE_ACPI_OLD: u32 = 14;
+        const TAG_TYPE_ACPI_NEW: u32 = 15;
+        const TAG_HEADER_LENGTH: usize = 8;
+
+        let total_size =
+            *unsafe { handler.map_physical_region::<u32>(info_address, mem::size_of::<u32>()) } as usize;
+        if total_size < TAG_HEADER_LENGTH {
+            return Err(RsdpError::InvalidBootInfo);
+        }
+
+        let info_mapping = unsafe { handler.map_physical_region::<u8>(info_address, total_size) };
+        let info = unsafe { slice::from_raw_parts(info_mapping.virtual_start.as_ptr() as *const u8, total_size) };
+        let read_u32 = |offset: usize| {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(&info[offset..(offset + 4)]);
+            u32::from_le_bytes(bytes)
+        };
+
+        /*
+         * The tags start after the fixed part of the structure, and are each aligned to 8 bytes.
+         */
+        let mut old_rsdp_offset = None;
+        let mut new_rsdp_offset = None;
+        let mut offset = TAG_HEADER_LENGTH;
+        while offset + TAG_HEADER_LENGTH <= total_size {
+            let tag_type = read_u32(offset);
+            let tag_size = read_u32(offset + 4) as usize;
+            if tag_size < TAG_HEADER_LENGTH || offset + tag_size > total_size {
+                return Err(RsdpError::InvalidBootInfo);
+            }
+
+            match tag_type {
+                TAG_TYPE_END => break,
+                TAG_TYPE_ACPI_OLD if tag_size >= TAG_HEADER_LENGTH + RSDP_V1_LENGTH => {
+                    old_rsdp_offset = Some(offset + TAG_HEADER_LENGTH)
+                }
+                TAG_TYPE_ACPI_NEW if tag_size >= TAG_HEADER_LENGTH + mem::size_of::<Rsdp>() => {
+                    new_rsdp_offset = Some(offset + TAG_HEADER_LENGTH)
+                }
+                _ => (),
+            }
+
+            offset += (tag_size + 7) & !7;
+        }
+
+        for rsdp_offset in new_rsdp_offset.into_iter().chain(old_rsdp_offset) {
+            let address = info_address + rsdp_offset;
+            let rsdp_mapping = unsafe { handler.map_physical_region::<Rsdp>(address, mem::size_of::<Rsdp>()) };
+
+            match rsdp_mapping.validate() {
+                Ok(()) => return Ok(rsdp_mapping),
+                Err(err) => warn!("Invalid RSDP found in Multiboot2 information at {:#x}: {:?}", address, err),
+            }
+        }
+
+        Err(RsdpError::NoValidRsdp)
+    }
+
+    /// This finds the RSDP using the `acpi_rsdp_addr` field of the Linux boot protocol's `boot_params` structure
+    /// (the "zero page") at `boot_params_address`. This field was added in version 2.14 of the boot protocol, and
+    /// may be zero if the bootloader did not find the RSDP.
+    ///
+    /// ### Safety
+    /// `boot_params_address` must be the physical address of a `boot_params` structure.
+    pub unsafe fn search_for_on_linux_boot_params<H>(
+        handler: H,
+        boot_params_address: usize,
+    ) -> Result<PhysicalMapping<H, Rsdp>, RsdpError>
+    where
+        H: AcpiHandler,
+    {
+        const BOOT_PARAMS_LENGTH: usize = 0x1000;
+        const ACPI_RSDP_ADDR_OFFSET: usize = 0x70;
+        const BOOT_FLAG_OFFSET: usize = 0x1fe;
+        const HEADER_MAGIC_OFFSET: usize = 0x202;
+        const VERSION_OFFSET: usize = 0x206;
+        const MIN_VERSION: u16 = 0x020e;
+
+        let rsdp_address = {
+            let boot_params_mapping =
+                unsafe { handler.map_physical_region::<u8>(boot_params_address, BOOT_PARAMS_LENGTH) };
+            let boot_params = unsafe {
+                slice::from_raw_parts(boot_params_mapping.virtual_start.as_ptr() as *const u8, BOOT_PARAMS_LENGTH)
+            };
+
+            if boot_params[BOOT_FLAG_OFFSET..(BOOT_FLAG_OFFSET + 2)] != [0x55, 0xaa]
+                || &boot_params[HEADER_MAGIC_OFFSET..(HEADER_MAGIC_OFFSET + 4)] != b"HdrS"
+            {
+                return Err(RsdpError::InvalidBootInfo);
+            }
+
+            let version = u16::from_le_bytes([boot_params[VERSION_OFFSET], boot_params[VERSION_OFFSET + 1]]);
+            if version < MIN_VERSION {
+                return Err(RsdpError::NoValidRsdp);
+            }
+
+            let mut rsdp_address = [0; 8];
+            rsdp_address.copy_from_slice(&boot_params[ACPI_RSDP_ADDR_OFFSET..(ACPI_RSDP_ADDR_OFFSET + 8)]);
+            u64::from_le_bytes(rsdp_address) as usize
+        };
+
+        if rsdp_address == 0 {
+            return Err(RsdpError::NoValidRsdp);
+        }
+
+        let rsdp_mapping = unsafe { handler.map_physical_region::<Rsdp>(rsdp_address, mem::size_of::<Rsdp>()) };
+        rsdp_mapping.validate()?;
+
+        Ok(rsdp_mapping)
+    }
+
     /// Checks that:
     ///     1) The signature is correct
     ///     2) The checksum is correct
     ///     3) For Version 2.0+, that the extension checksum is correct
     pub fn validate(&self) -> Result<(), RsdpError> {
-        const RSDP_V1_LENGTH: usize = 20;
-
         // Check the signature
         if &self.signature != RSDP_SIGNATURE {
             return Err(RsdpError::IncorrectSignature);
@@ -207,5 +368,7 @@ const EBDA_END: usize = 0x9ffff;
 const RSDP_BIOS_AREA_START: usize = 0xe0000;
 /// The end of the main BIOS area below 1mb in which to search for the RSDP (Root System Description Pointer)
 const RSDP_BIOS_AREA_END: usize = 0xfffff;
+/// The length of the RSDP in ACPI Version 1.0, which does not include the fields added in Version 2.0
+const RSDP_V1_LENGTH: usize = 20;
 /// The RSDP (Root System Description Pointer)'s signature, "RSD PTR " (note trailing space)
-const RSDP_SIGNATURE: &'static [u8; 8] = b"RSD PTR ";
+const RSDP_SIGNATURE: &[u8; 8] = b"RSD PTR ";
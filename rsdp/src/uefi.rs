@@ -0,0 +1,40 @@
+//! On UEFI systems, the firmware provides the address of the RSDP in the Configuration Table of the
+//! `EFI_SYSTEM_TABLE`. These types follow the layout of the Configuration Table, so that a bootloader can pass it
+//! to [`Rsdp::search_for_on_uefi`](crate::Rsdp::search_for_on_uefi) without depending on a UEFI crate.
+
+/// A UEFI GUID, in the layout used by `EFI_GUID`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(C)]
+pub struct Guid {
+    pub data1: u32,
+    pub data2: u16,
+    pub data3: u16,
+    pub data4: [u8; 8],
+}
+
+/// The GUID of the Configuration Table entry that points to an ACPI 1.0 RSDP:
+/// `eb9d2d30-2d88-11d3-9a16-0090273fc14d`.
+pub const ACPI_GUID: Guid = Guid {
+    data1: 0xeb9d2d30,
+    data2: 0x2d88,
+    data3: 0x11d3,
+    data4: [0x9a, 0x16, 0x00, 0x90, 0x27, 0x3f, 0xc1, 0x4d],
+};
+
+/// The GUID of the Configuration Table entry that points to an ACPI 2.0 or later RSDP:
+/// `8868e871-e4f1-11d3-bc22-0080c73c8881`.
+pub const ACPI2_GUID: Guid = Guid {
+    data1: 0x8868e871,
+    data2: 0xe4f1,
+    data3: 0x11d3,
+    data4: [0xbc, 0x22, 0x00, 0x80, 0xc7, 0x3c, 0x88, 0x81],
+};
+
+/// An entry of the UEFI Configuration Table, in the layout used by `EFI_CONFIGURATION_TABLE`.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct ConfigurationTableEntry {
+    pub vendor_guid: Guid,
+    /// The address of the table. UEFI identity-maps memory, so this is also the physical address of the table.
+    pub vendor_table: usize,
+}